@@ -1,21 +1,34 @@
+mod http;
+
 use crate::{
+    auth,
     error::OneBotApiError,
+    event_client::{EventClientSender, OneBotEventClient},
     internal::api::request::{self, OneBotRequest},
 };
+use http::HttpTransport;
 use futures_util::{SinkExt, StreamExt};
+use log::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
-use tokio::net::TcpStream;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::oneshot;
-use tokio::sync::mpsc;
+use tokio::sync::{Mutex, mpsc};
+use tokio::task::JoinHandle;
 use tokio::time::Duration;
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async, tungstenite};
+use tokio_tungstenite::{WebSocketStream, tungstenite};
 use dashmap::DashMap;
 use std::num::NonZeroUsize;
 
 // 用于控制并行处理消息的数量
 const MAX_CONCURRENT_HANDLERS: usize = 32;
+// 重连等待时间的下限与上限
+const RECONNECT_BACKOFF_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_BACKOFF_MAX: Duration = Duration::from_secs(60);
+// 首次重试前的等待时间，之后每次翻倍
+const RETRY_DELAY: Duration = Duration::from_millis(200);
 
 impl From<mpsc::error::SendError<String>> for OneBotApiError {
     fn from(_: mpsc::error::SendError<String>) -> Self {
@@ -29,44 +42,240 @@ pub struct OneBotResponse {
     pub retcode: i32,
     pub data: Option<Value>,
     pub echo: Option<String>,
+    /// 错误信息（go-cqhttp 为 `msg`）
+    #[serde(default, alias = "msg")]
+    pub message: Option<String>,
+    /// 错误的详细描述
+    #[serde(default)]
+    pub wording: Option<String>,
+}
+
+impl OneBotResponse {
+    /// `ok` 为成功，`async` 为请求已提交异步执行，其余视为失败
+    #[allow(clippy::result_large_err)]
+    fn into_result(self) -> Result<Option<Value>, OneBotApiError> {
+        match self.status.as_str() {
+            "ok" | "async" => Ok(self.data),
+            _ => Err(OneBotApiError::Failed {
+                retcode: self.retcode,
+                message: self
+                    .wording
+                    .or(self.message)
+                    .filter(|message| !message.is_empty())
+                    .unwrap_or_else(|| self.status.clone()),
+            }),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct OneBotApiClient {
-    pending_requests: Arc<DashMap<String, oneshot::Sender<OneBotResponse>>>,
-    tx: mpsc::UnboundedSender<String>,
+    transport: ApiTransport,
+    // 可重试错误的最大重试次数
+    retries: u32,
+}
+
+/// API 调用的传输方式
+#[derive(Debug, Clone)]
+enum ApiTransport {
+    WebSocket(WebSocketTransport),
+    Http(HttpTransport),
 }
 
 impl OneBotApiClient {
-    pub async fn new(url: &str) -> Result<Self, OneBotApiError> {
-        let (ws_stream, _) = connect_async(url).await?;
-        let (tx, rx) = mpsc::unbounded_channel();
-        let pending_requests = Arc::new(DashMap::with_capacity(128));
-        
-        let client = Self {
-            pending_requests: pending_requests.clone(),
-            tx,
+    /// 通过正向 WebSocket 连接 `/api` 端点
+    ///
+    /// 连接在后台建立，断开后会自动重连。
+    pub fn new(url: &str, access_token: Option<&str>) -> Self {
+        WebSocketTransport::connect(url, access_token, None).into()
+    }
+
+    /// 通过正向 WebSocket 连接通用端点 `/`
+    ///
+    /// 连接在后台建立，断开后会自动重连；每次连接成功都会向 `event_clients`
+    /// 投递一个接收该连接事件帧的事件客户端。
+    pub fn universal(
+        url: &str,
+        access_token: Option<&str>,
+        event_clients: EventClientSender,
+    ) -> Self {
+        WebSocketTransport::connect(url, access_token, Some(event_clients)).into()
+    }
+
+    /// 通过 HTTP 调用 API
+    ///
+    /// # 参数
+    /// - `url`: OneBot 实现的 HTTP 服务地址
+    /// - `access_token`: 访问令牌
+    pub fn http(url: &str, access_token: Option<&str>) -> Result<Self, OneBotApiError> {
+        Ok(HttpTransport::new(url, access_token)?.into())
+    }
+
+    /// 设置可重试错误（见 [`OneBotApiError::is_retryable`]）的最大重试次数
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub async fn call_api<R: OneBotRequest>(
+        &self,
+        echo: u64,
+        params: R,
+    ) -> Result<R::RESPONSE, OneBotApiError> {
+        let kind = params.into_kind();
+        let mut attempt = 0;
+        let response = loop {
+            let result = match &self.transport {
+                ApiTransport::WebSocket(ws) => ws.call(echo, kind.clone()).await,
+                ApiTransport::Http(http) => http.call(kind.clone()).await,
+            };
+            match result {
+                Err(e) if e.is_retryable() && attempt < self.retries => {
+                    attempt += 1;
+                    warn!("API调用失败，正在重试 ({}/{}): {}", attempt, self.retries, e);
+                    tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt - 1)).await;
+                }
+                result => break result?,
+            }
         };
 
+        // 快速解析响应
+        let data = response.into_result()?;
+        match serde_json::from_value(data.unwrap_or(Value::Null)) {
+            Ok(result) => Ok(result),
+            Err(_) => Err(OneBotApiError::InvalidMessage)
+        }
+    }
+}
+
+impl From<WebSocketTransport> for OneBotApiClient {
+    fn from(value: WebSocketTransport) -> Self {
+        Self {
+            transport: ApiTransport::WebSocket(value),
+            retries: 0,
+        }
+    }
+}
+
+impl From<HttpTransport> for OneBotApiClient {
+    fn from(value: HttpTransport) -> Self {
+        Self {
+            transport: ApiTransport::Http(value),
+            retries: 0,
+        }
+    }
+}
+
+/// 基于 WebSocket 的 API 传输，通过 `echo` 匹配请求与响应
+#[derive(Debug, Clone)]
+pub struct WebSocketTransport {
+    pending_requests: Arc<DashMap<String, oneshot::Sender<OneBotResponse>>>,
+    tx: mpsc::UnboundedSender<String>,
+    // 待发送请求的接收端，由当前接入的连接持有
+    rx: Arc<Mutex<mpsc::UnboundedReceiver<String>>>,
+    // 当前接入的连接数量
+    connections: Arc<AtomicUsize>,
+}
+
+impl WebSocketTransport {
+    /// 连接正向 WebSocket，断开后按指数退避自动重连
+    ///
+    /// # 参数
+    /// - `url`: WebSocket 地址
+    /// - `access_token`: 访问令牌
+    /// - `event_clients`: 若连接同时承载事件（通用连接），每次连接成功都会向此投递事件客户端
+    pub fn connect(
+        url: &str,
+        access_token: Option<&str>,
+        event_clients: Option<EventClientSender>,
+    ) -> Self {
+        let transport = Self::detached();
+
         // 启动WebSocket处理任务
-        tokio::spawn(Self::process_websocket(ws_stream, rx, pending_requests));
+        tokio::spawn(transport.clone().reconnect_loop(
+            url.to_string(),
+            access_token.map(str::to_string),
+            event_clients,
+        ));
 
-        Ok(client)
+        transport
     }
 
-    async fn process_websocket(
-        ws_stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
-        mut rx: mpsc::UnboundedReceiver<String>,
-        pending_requests: Arc<DashMap<String, oneshot::Sender<OneBotResponse>>>,
+    async fn reconnect_loop(
+        self,
+        url: String,
+        access_token: Option<String>,
+        event_clients: Option<EventClientSender>,
     ) {
+        let mut backoff = RECONNECT_BACKOFF_MIN;
+        loop {
+            match auth::connect(&url, access_token.as_deref()).await {
+                Ok(ws_stream) => {
+                    info!("API WebSocket连接成功");
+                    backoff = RECONNECT_BACKOFF_MIN;
+                    let events = event_clients.as_ref().map(|event_clients| {
+                        let (tx, rx) = mpsc::unbounded_channel();
+                        let _ = event_clients.send(OneBotEventClient::from_channel(rx));
+                        tx
+                    });
+                    let _ = self.attach(ws_stream, events).await;
+                    warn!("API WebSocket连接已断开");
+                }
+                Err(e) => error!("连接API WebSocket失败: {}", e),
+            }
+            info!("{} 秒后重新连接API WebSocket...", backoff.as_secs());
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(RECONNECT_BACKOFF_MAX);
+        }
+    }
+
+    /// 创建尚未接入连接的传输
+    ///
+    /// 没有接入任何连接时，发出的请求会立即以 [`OneBotApiError::Disconnected`] 失败
+    /// （可由 `api-retries` 重试），不会等待连接接入。
+    pub fn detached() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            pending_requests: Arc::new(DashMap::with_capacity(128)),
+            tx,
+            rx: Arc::new(Mutex::new(rx)),
+            connections: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// 将一条 WebSocket 连接接入传输
+    ///
+    /// # 参数
+    /// - `ws_stream`: 已建立的 WebSocket 连接
+    /// - `events`: 若连接同时承载事件（通用连接），事件帧会被转发至此
+    pub fn attach<S>(
+        &self,
+        ws_stream: WebSocketStream<S>,
+        events: Option<mpsc::UnboundedSender<String>>,
+    ) -> JoinHandle<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        self.connections.fetch_add(1, Ordering::AcqRel);
+        tokio::spawn(self.clone().process_websocket(ws_stream, events))
+    }
+
+    async fn process_websocket<S>(
+        self,
+        ws_stream: WebSocketStream<S>,
+        events: Option<mpsc::UnboundedSender<String>>,
+    ) where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
         let (mut ws_writer, mut ws_reader) = ws_stream.split();
+        let rx = self.rx.clone();
         
         // 创建信号量控制并发处理的消息数量
         let semaphore = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_HANDLERS));
         
         // 处理消息接收的任务
-        let receiver_task = {
-            let pending_requests = pending_requests.clone();
+        let mut receiver_task = {
+            let pending_requests = self.pending_requests.clone();
             let semaphore = semaphore.clone();
             
             tokio::spawn(async move {
@@ -76,8 +285,25 @@ impl OneBotApiClient {
                 loop {
                     tokio::select! {
                         // 收集消息成批处理
-                        Some(Ok(msg)) = ws_reader.next() => {
-                            batch.push(msg);
+                        msg = ws_reader.next() => {
+                            let Some(Ok(msg)) = msg else {
+                                // 连接关闭或出错
+                                break;
+                            };
+                            let tungstenite::Message::Text(text) = msg else {
+                                continue;
+                            };
+                            let Ok(value) = serde_json::from_str::<Value>(&text) else {
+                                continue;
+                            };
+                            // 带有 post_type 的是事件帧，按接收顺序转交给事件处理
+                            if value.get("post_type").is_some() {
+                                if let Some(events) = &events {
+                                    let _ = events.send(text.as_str().to_owned());
+                                }
+                                continue;
+                            }
+                            batch.push(value);
                             // 如果批次已满，立即处理
                             if batch.len() >= 16 {
                                 let msgs_to_process = std::mem::replace(&mut batch, Vec::with_capacity(16));
@@ -90,16 +316,24 @@ impl OneBotApiClient {
                                 let msgs_to_process = std::mem::replace(&mut batch, Vec::with_capacity(16));
                                 Self::process_message_batch(msgs_to_process, &pending_requests, &semaphore).await;
                             }
+                            // 事件客户端已放弃该连接（如心跳超时），断开以触发重连
+                            if events.as_ref().is_some_and(|events| events.is_closed()) {
+                                break;
+                            }
                         }
-                        // 如果WebSocket关闭，退出循环
-                        else => break,
                     }
                 }
+                // 处理断开前收到的响应
+                if !batch.is_empty() {
+                    Self::process_message_batch(batch, &pending_requests, &semaphore).await;
+                }
             })
         };
         
         // 处理消息发送的任务
-        let sender_task = tokio::spawn(async move {
+        let mut sender_task = tokio::spawn(async move {
+            // 同一时间只有一条连接负责发送请求
+            let mut rx = rx.lock().await;
             // 发送消息批处理
             let mut batch = Vec::with_capacity(16);
             let mut interval = tokio::time::interval(Duration::from_millis(5));
@@ -130,14 +364,26 @@ impl OneBotApiClient {
         
         // 等待任意一个任务结束
         tokio::select! {
-            _ = sender_task => {},
-            _ = receiver_task => {},
+            _ = &mut sender_task => {},
+            _ = &mut receiver_task => {},
+        }
+        // 结束另一个任务，释放请求接收端以便新连接接管
+        sender_task.abort();
+        receiver_task.abort();
+        let _ = sender_task.await;
+
+        // 最后一条连接断开时，丢弃尚未发送的请求，并使等待中的请求立即失败
+        if self.connections.fetch_sub(1, Ordering::AcqRel) == 1 {
+            if let Ok(mut rx) = self.rx.try_lock() {
+                while rx.try_recv().is_ok() {}
+            }
+            self.pending_requests.clear();
         }
     }
     
-    // 批量处理接收消息
+    // 批量处理接收到的 API 响应
     async fn process_message_batch(
-        messages: Vec<tungstenite::Message>,
+        messages: Vec<Value>,
         pending_requests: &Arc<DashMap<String, oneshot::Sender<OneBotResponse>>>,
         semaphore: &Arc<tokio::sync::Semaphore>,
     ) {
@@ -167,10 +413,12 @@ impl OneBotApiClient {
     }
     
     // 批量发送消息
-    async fn send_message_batch(
-        ws_writer: &mut futures_util::stream::SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, tungstenite::Message>,
+    async fn send_message_batch<S>(
+        ws_writer: &mut futures_util::stream::SplitSink<WebSocketStream<S>, tungstenite::Message>,
         messages: Vec<String>,
-    ) {
+    ) where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
         for msg in messages {
             if let Err(e) = ws_writer.send(tungstenite::Message::Text(msg.into())).await {
                 eprintln!("Failed to send message: {}", e);
@@ -180,13 +428,17 @@ impl OneBotApiClient {
         }
     }
 
-    pub async fn call_api<R: OneBotRequest>(
+    async fn call(
         &self,
         echo: u64,
-        params: R,
-    ) -> Result<R::RESPONSE, OneBotApiError> {
+        kind: request::ApiRequestKind,
+    ) -> Result<OneBotResponse, OneBotApiError> {
+        if self.connections.load(Ordering::Acquire) == 0 {
+            return Err(OneBotApiError::Disconnected);
+        }
+
         let echo_str = echo.to_string();
-        let request = request::ApiRequest::new(echo_str.clone(), params.into_kind());
+        let request = request::ApiRequest::new(echo_str.clone(), kind);
         
         // 提前准备好所有需要的数据，减少锁定后的操作时间
         let request_str = serde_json::to_string(&request)?;
@@ -209,26 +461,18 @@ impl OneBotApiClient {
                 // 超时时清理未完成的请求
                 self.pending_requests.remove(&echo_str);
                 OneBotApiError::Timeout
-            })??;
+            })?
+            // 连接断开时等待中的请求会被丢弃
+            .map_err(|_| OneBotApiError::Disconnected)?;
 
-        // 快速解析响应
-        match serde_json::from_value(response.data.unwrap_or(Value::Null)) {
-            Ok(result) => Ok(result),
-            Err(_) => Err(OneBotApiError::InvalidMessage)
-        }
+        Ok(response)
     }
 
     async fn handle_message(
-        msg: tungstenite::Message,
+        value: Value,
         pending_requests: Arc<DashMap<String, oneshot::Sender<OneBotResponse>>>,
     ) {
-        // 提前解析消息，避免在持有锁的情况下执行
-        let msg_str = match msg {
-            tungstenite::Message::Text(text) => text,
-            _ => return,
-        };
-
-        let response: OneBotResponse = match serde_json::from_str(&msg_str) {
+        let response: OneBotResponse = match serde_json::from_value(value) {
             Ok(resp) => resp,
             Err(_) => return,
         };
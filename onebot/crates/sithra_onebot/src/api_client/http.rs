@@ -0,0 +1,58 @@
+use super::OneBotResponse;
+use crate::{error::OneBotApiError, internal::api::request::ApiRequestKind};
+use reqwest::StatusCode;
+use serde_json::Value;
+use tokio::time::Duration;
+
+/// 基于 HTTP 的 API 传输
+///
+/// 每次调用向 `{url}/{action}` 发送一次 POST 请求，请求体为 JSON 格式的参数。
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    client: reqwest::Client,
+    url: String,
+    access_token: Option<String>,
+}
+
+impl HttpTransport {
+    pub fn new(url: &str, access_token: Option<&str>) -> Result<Self, OneBotApiError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+        Ok(Self {
+            client,
+            url: url.trim_end_matches('/').to_string(),
+            access_token: access_token.map(str::to_string),
+        })
+    }
+
+    pub async fn call(&self, kind: ApiRequestKind) -> Result<OneBotResponse, OneBotApiError> {
+        // `ApiRequestKind` 序列化为 `{"action": ..., "params": ...}`
+        let mut request = serde_json::to_value(kind)?;
+        let action = match request.get("action").and_then(Value::as_str) {
+            Some(action) => action.to_string(),
+            None => return Err(OneBotApiError::InvalidMessage),
+        };
+        let params = request
+            .get_mut("params")
+            .map(Value::take)
+            .unwrap_or_else(|| Value::Object(Default::default()));
+
+        let mut request = self
+            .client
+            .post(format!("{}/{}", self.url, action))
+            .json(&params);
+        if let Some(token) = &self.access_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await?;
+        let response = match response.status() {
+            StatusCode::UNAUTHORIZED => return Err(OneBotApiError::Unauthorized),
+            StatusCode::FORBIDDEN => return Err(OneBotApiError::Forbidden),
+            _ => response.error_for_status()?,
+        };
+
+        Ok(response.json::<OneBotResponse>().await?)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::error::OneBotApiError;
+use std::borrow::Cow;
+use subtle::ConstantTimeEq;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{
+        client::IntoClientRequest,
+        handshake::server::Request,
+        http::{HeaderValue, StatusCode, header::AUTHORIZATION},
+    },
+};
+
+/// 连接正向 WebSocket，配置了访问令牌时附带 `Authorization` 请求头
+pub async fn connect(
+    url: &str,
+    access_token: Option<&str>,
+) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, OneBotApiError> {
+    let mut request = url
+        .into_client_request()
+        .map_err(OneBotApiError::from_handshake)?;
+    if let Some(token) = access_token {
+        let value = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|e| OneBotApiError::Internal(e.to_string()))?;
+        request.headers_mut().insert(AUTHORIZATION, value);
+    }
+    let (ws_stream, _) = connect_async(request)
+        .await
+        .map_err(OneBotApiError::from_handshake)?;
+    Ok(ws_stream)
+}
+
+/// 校验反向 WebSocket 握手请求中的访问令牌
+///
+/// 支持 `Authorization: Bearer <token>` 请求头与 `?access_token=<token>` 查询参数（百分号编码）。
+/// 未提供令牌时返回 401，令牌错误时返回 403。
+pub fn verify_request(request: &Request, access_token: Option<&str>) -> Result<(), StatusCode> {
+    let Some(expected) = access_token else {
+        return Ok(());
+    };
+    let from_header = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| {
+            value
+                .strip_prefix("Bearer ")
+                .or_else(|| value.strip_prefix("Token "))
+                .unwrap_or(value)
+        });
+    let from_query = request.uri().query().and_then(|query| {
+        form_urlencoded::parse(query.as_bytes())
+            .find_map(|(key, value)| (key == "access_token").then_some(value))
+    });
+    let token = from_header.map(Cow::Borrowed).or(from_query);
+    match token {
+        // 以固定时间比较，避免通过响应时间逐字节猜测令牌
+        Some(token) if bool::from(token.as_bytes().ct_eq(expected.as_bytes())) => Ok(()),
+        Some(_) => Err(StatusCode::FORBIDDEN),
+        None => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handshake(uri: &str, authorization: Option<&str>) -> Request {
+        let mut request = Request::builder().uri(uri);
+        if let Some(authorization) = authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        request.body(()).unwrap()
+    }
+
+    #[test]
+    fn accepts_bearer_header() {
+        let request = handshake("/", Some("Bearer s3cret"));
+        assert_eq!(verify_request(&request, Some("s3cret")), Ok(()));
+    }
+
+    #[test]
+    fn accepts_percent_encoded_query_token() {
+        let request = handshake("/event?foo=1&access_token=a%2Bb%20c%26d", None);
+        assert_eq!(verify_request(&request, Some("a+b c&d")), Ok(()));
+    }
+
+    #[test]
+    fn rejects_wrong_token() {
+        let request = handshake("/", Some("Bearer wrong"));
+        assert_eq!(verify_request(&request, Some("s3cret")), Err(StatusCode::FORBIDDEN));
+        let request = handshake("/?access_token=wrong", None);
+        assert_eq!(verify_request(&request, Some("s3cret")), Err(StatusCode::FORBIDDEN));
+    }
+
+    #[test]
+    fn rejects_missing_token() {
+        let request = handshake("/?foo=bar", None);
+        assert_eq!(verify_request(&request, Some("s3cret")), Err(StatusCode::UNAUTHORIZED));
+    }
+
+    #[test]
+    fn accepts_anything_without_configured_token() {
+        assert_eq!(verify_request(&handshake("/", None), None), Ok(()));
+        assert_eq!(verify_request(&handshake("/", Some("Bearer any")), None), Ok(()));
+    }
+}
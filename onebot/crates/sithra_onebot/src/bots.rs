@@ -0,0 +1,90 @@
+use crate::api_client::OneBotApiClient;
+use dashmap::{DashMap, DashSet};
+use std::sync::{Arc, LazyLock, OnceLock};
+
+// 已登录的机器人账号，键为 self_id
+static BOTS: LazyLock<DashMap<String, OneBotApiClient>> = LazyLock::new(DashMap::new);
+// 与配置不符而被拒绝的账号
+static REJECTED: LazyLock<DashSet<String>> = LazyLock::new(DashSet::new);
+// 各账号配置的登录状态，键为账号配置的序号
+static LOGINS: LazyLock<DashMap<usize, Login>> = LazyLock::new(DashMap::new);
+
+/// 账号配置实际登录的账号，由该配置的 API 与事件连接共享
+#[derive(Debug, Clone, Default)]
+pub struct Login {
+    self_id: Arc<OnceLock<String>>,
+    // 可由事件登记账号时使用的 API 客户端，见 [`Login::claim`]
+    api_client: Arc<OnceLock<OneBotApiClient>>,
+}
+
+impl Login {
+    /// 登录的账号，获取到登录信息前为 `None`
+    pub fn self_id(&self) -> Option<&str> {
+        self.self_id.get().map(String::as_str)
+    }
+
+    pub fn set(&self, self_id: String) {
+        let _ = self.self_id.set(self_id);
+    }
+
+    /// 允许在获取到登录信息前，以收到的事件中的账号登记该配置
+    ///
+    /// 用于 HTTP POST 上报：API 可能不可达，`get_login_info` 迟迟无法完成，
+    /// 但快速操作等不依赖 API 的调用仍需要能路由到本适配器。
+    pub fn allow_claim(&self, api_client: OneBotApiClient) {
+        let _ = self.api_client.set(api_client);
+    }
+
+    /// 以该账号登记尚未获取到登录信息的配置
+    ///
+    /// 未调用过 [`Login::allow_claim`]、已确定登录账号或账号已被拒绝时不做任何事。
+    pub fn claim(&self, self_id: &str) {
+        let Some(api_client) = self.api_client.get() else {
+            return;
+        };
+        if is_rejected(self_id) || self.self_id.set(self_id.to_string()).is_err() {
+            return;
+        }
+        register(self_id.to_string(), api_client.clone());
+    }
+}
+
+/// 获取账号配置的登录状态
+///
+/// # 参数
+/// - `index`: 账号配置的序号，见 [`crate::config::BotConfig::index`]
+pub fn login(index: usize) -> Login {
+    LOGINS.entry(index).or_default().clone()
+}
+
+/// 登记已登录的机器人账号
+pub fn register(self_id: String, api_client: OneBotApiClient) {
+    REJECTED.remove(&self_id);
+    BOTS.insert(self_id, api_client);
+}
+
+/// 拒绝使用该账号，之后收到的该账号的事件将被忽略
+pub fn reject(self_id: String) {
+    BOTS.remove(&self_id);
+    REJECTED.insert(self_id);
+}
+
+/// 取消登记账号
+pub fn unregister(self_id: &str) {
+    BOTS.remove(self_id);
+}
+
+/// 账号是否已登录
+pub fn contains(self_id: &str) -> bool {
+    BOTS.contains_key(self_id)
+}
+
+/// 账号是否已被拒绝
+pub fn is_rejected(self_id: &str) -> bool {
+    REJECTED.contains(self_id)
+}
+
+/// 获取账号对应的 API 客户端
+pub fn get(self_id: &str) -> Option<OneBotApiClient> {
+    BOTS.get(self_id).map(|client| client.clone())
+}
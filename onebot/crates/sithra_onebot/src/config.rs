@@ -1,18 +1,129 @@
 use std::{fs::File, io::Write};
 
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 pub const DEFAULT_CONFIG: &str = include_str!("../static/config.toml");
 
+/// API 调用的传输方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiTransportKind {
+    /// 通过 WebSocket 的 `/api` 端点调用
+    #[default]
+    WebSocket,
+    /// 通过 HTTP POST 调用
+    Http,
+}
+
+/// 连接模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionMode {
+    /// 正向 WebSocket，由适配器主动连接 OneBot 实现
+    #[default]
+    Forward,
+    /// 反向 WebSocket，由 OneBot 实现连接适配器
+    Reverse,
+    /// HTTP POST 事件上报，由 OneBot 实现推送事件
+    Webhook,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct OneBotConfig {
-    #[serde(rename = "ws-url")]
+    /// 顶层的连接配置，未配置 `bots` 时作为唯一的账号使用
+    #[serde(flatten)]
+    pub bot: BotConfig,
+    /// 多个账号的连接配置
+    #[serde(default)]
+    pub bots: Vec<BotConfig>,
+    #[serde(rename = "media-max-size", default = "default_media_max_size")]
+    pub media_max_size: u64,
+    #[serde(rename = "media-inline-local", default)]
+    pub media_inline_local: bool,
+}
+
+/// 单个账号的连接配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct BotConfig {
+    /// 在所有账号配置中的序号，用于关联同一账号的 API 与事件连接
+    #[serde(skip)]
+    pub index: usize,
+    #[serde(default)]
+    pub mode: ConnectionMode,
+    #[serde(rename = "ws-url", default)]
     pub ws_url: String,
-    #[serde(rename = "self-id")]
-    pub self_id: String,
+    #[serde(rename = "ws-universal", default)]
+    pub ws_universal: bool,
+    #[serde(rename = "reverse-listen", default = "default_reverse_listen")]
+    pub reverse_listen: String,
+    #[serde(rename = "api-transport", default)]
+    pub api_transport: ApiTransportKind,
+    #[serde(rename = "http-url", default)]
+    pub http_url: String,
+    #[serde(rename = "api-retries", default)]
+    pub api_retries: u32,
+    #[serde(rename = "webhook-listen", default = "default_webhook_listen")]
+    pub webhook_listen: String,
+    #[serde(rename = "webhook-secret", default)]
+    pub webhook_secret: Option<String>,
+    #[serde(rename = "quick-operation-timeout", default)]
+    pub quick_operation_timeout: u64,
+    #[serde(rename = "access-token", default)]
+    pub access_token: Option<String>,
+    #[serde(rename = "self-id", default, deserialize_with = "deserialize_self_id")]
+    pub self_id: Option<String>,
+}
+
+/// `self-id` 可以写作整数或字符串，留空视为未配置
+fn deserialize_self_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SelfId {
+        Int(u64),
+        Str(String),
+    }
+    Ok(match Option::<SelfId>::deserialize(deserializer)? {
+        Some(SelfId::Int(id)) => Some(id.to_string()),
+        Some(SelfId::Str(id)) if !id.is_empty() => Some(id),
+        _ => None,
+    })
+}
+
+fn default_reverse_listen() -> String {
+    "127.0.0.1:8080".to_string()
+}
+
+fn default_webhook_listen() -> String {
+    "127.0.0.1:5701".to_string()
+}
+
+fn default_media_max_size() -> u64 {
+    30
+}
+
+impl BotConfig {
+    /// 访问令牌，未配置或为空时返回 `None`
+    pub fn access_token(&self) -> Option<&str> {
+        self.access_token.as_deref().filter(|token| !token.is_empty())
+    }
 }
 
 impl OneBotConfig {
+    /// 所有账号的连接配置
+    pub fn bots(&self) -> Vec<BotConfig> {
+        let bots = if self.bots.is_empty() {
+            vec![self.bot.clone()]
+        } else {
+            self.bots.clone()
+        };
+        bots.into_iter()
+            .enumerate()
+            .map(|(index, bot)| BotConfig { index, ..bot })
+            .collect()
+    }
+
     pub fn load() -> Result<Self, toml::de::Error> {
         let file_path = sithra_common::data_path!().join("config.toml");
         let config = if !file_path.exists() {
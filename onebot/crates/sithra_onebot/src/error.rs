@@ -1,36 +1,76 @@
 use ioevent::error::CallSubscribeError;
 use thiserror::Error;
-use tokio_tungstenite::tungstenite;
-use tokio::sync::oneshot;
+use tokio_tungstenite::tungstenite::{self, http::StatusCode};
 
 #[derive(Debug, Error)]
 pub enum OneBotApiError {
     #[error("WebSocket错误: {0}")]
     WebSocket(#[from] tungstenite::Error),
 
+    #[error("HTTP错误: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("IO错误: {0}")]
+    Io(#[from] std::io::Error),
+
     #[error("JSON错误: {0}")]
     Json(#[from] serde_json::Error),
 
-    #[error("通道错误: {0}")]
-    Channel(#[from] oneshot::error::RecvError),
+    #[error("连接已断开")]
+    Disconnected,
+
+    #[error("鉴权失败: 未提供访问令牌")]
+    Unauthorized,
+
+    #[error("鉴权失败: 访问令牌错误")]
+    Forbidden,
 
     #[error("请求超时")]
     Timeout,
 
+    #[error("OneBot 实现拒绝了请求 (retcode {retcode}): {message}")]
+    Failed { retcode: i32, message: String },
+
+    #[error("账号 {0} 未连接")]
+    BotNotConnected(String),
+
+    #[error("该操作仅支持群聊")]
+    NotGroupChannel,
+
     #[error("消息格式错误")]
     InvalidMessage,
 
+    #[error("媒体文件过大: {size} 字节，上限为 {limit} 字节")]
+    MediaTooLarge { size: u64, limit: u64 },
+
+    #[error("媒体文件无效: {0}")]
+    InvalidMedia(String),
+
     #[error("内部错误: {0}")]
     Internal(String),
 }
 
 impl OneBotApiError {
+    /// 将握手被拒绝（401/403）的错误转换为鉴权错误
+    pub fn from_handshake(error: tungstenite::Error) -> Self {
+        if let tungstenite::Error::Http(response) = &error {
+            match response.status() {
+                StatusCode::UNAUTHORIZED => return OneBotApiError::Unauthorized,
+                StatusCode::FORBIDDEN => return OneBotApiError::Forbidden,
+                _ => {}
+            }
+        }
+        OneBotApiError::WebSocket(error)
+    }
+
     pub fn is_retryable(&self) -> bool {
-        matches!(
-            self,
-            OneBotApiError::WebSocket(_) | 
-            OneBotApiError::Timeout
-        )
+        match self {
+            OneBotApiError::WebSocket(_)
+            | OneBotApiError::Disconnected
+            | OneBotApiError::Timeout => true,
+            OneBotApiError::Http(e) => e.is_connect() || e.is_timeout(),
+            _ => false,
+        }
     }
 }
 
@@ -1,35 +1,118 @@
+use crate::auth;
+use crate::bots::Login;
 use crate::internal::event::InternalOnebotEvent;
 use crate::error::OneBotApiError;
-use futures_util::StreamExt;
-use tokio::net::TcpStream;
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async, tungstenite};
+use futures_util::{Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::{LazyLock, Mutex};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::mpsc;
+use tokio_tungstenite::{WebSocketStream, tungstenite};
+
+type EventStream =
+    Pin<Box<dyn Stream<Item = Result<tungstenite::Message, tungstenite::Error>> + Send>>;
+
+type EventClients = (
+    mpsc::UnboundedSender<OneBotEventClient>,
+    Mutex<Option<mpsc::UnboundedReceiver<OneBotEventClient>>>,
+);
+
+// 由 API 一侧建立的事件连接（反向 WebSocket、通用连接），各账号共用，由 `main` 取走后处理
+static EVENT_CLIENTS: LazyLock<EventClients> = LazyLock::new(|| {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (tx, Mutex::new(Some(rx)))
+});
+
+/// 获取账号配置的事件连接投递端，接收端可通过 [`take_event_clients`] 取走
+pub fn register_event_clients(login: Login) -> EventClientSender {
+    EventClientSender {
+        tx: EVENT_CLIENTS.0.clone(),
+        login,
+    }
+}
+
+/// 事件连接的投递端，投递的事件连接会关联到所属账号配置的登录状态
+#[derive(Debug, Clone)]
+pub struct EventClientSender {
+    tx: mpsc::UnboundedSender<OneBotEventClient>,
+    login: Login,
+}
+
+impl EventClientSender {
+    pub fn send(
+        &self,
+        client: OneBotEventClient,
+    ) -> Result<(), mpsc::error::SendError<OneBotEventClient>> {
+        self.tx.send(client.bind(self.login.clone()))
+    }
+}
+
+/// 取走事件连接的接收端
+pub fn take_event_clients() -> Option<mpsc::UnboundedReceiver<OneBotEventClient>> {
+    EVENT_CLIENTS.1.lock().unwrap().take()
+}
 
-#[derive(Debug)]
 pub struct OneBotEventClient {
-    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ws: EventStream,
+    // 该连接所属账号配置的登录状态
+    login: Login,
 }
 
 impl OneBotEventClient {
-    pub async fn new(url: &str) -> Result<Self, OneBotApiError> {
-        let (ws_stream, _) = connect_async(url).await?;
-        Ok(Self {
-            ws: ws_stream,
-        })
+    pub async fn new(url: &str, access_token: Option<&str>) -> Result<Self, OneBotApiError> {
+        let ws_stream = auth::connect(url, access_token).await?;
+        Ok(Self::from_stream(ws_stream))
+    }
+
+    /// 使用已建立的 WebSocket 连接（如反向 WebSocket）创建事件客户端
+    pub fn from_stream<S>(ws_stream: WebSocketStream<S>) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        Self {
+            ws: Box::pin(ws_stream),
+            login: Login::default(),
+        }
+    }
+
+    /// 使用转发的事件帧创建事件客户端，用于同时承载 API 与事件的连接
+    pub fn from_channel(rx: mpsc::UnboundedReceiver<String>) -> Self {
+        let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+            let text = rx.recv().await?;
+            Some((Ok(tungstenite::Message::Text(text.into())), rx))
+        });
+        Self {
+            ws: Box::pin(stream),
+            login: Login::default(),
+        }
+    }
+
+    /// 关联到账号配置的登录状态，用于校验事件的 `self_id`
+    pub fn bind(mut self, login: Login) -> Self {
+        self.login = login;
+        self
+    }
+
+    /// 该连接所属账号配置的登录状态
+    pub fn login(&self) -> &Login {
+        &self.login
     }
 
     pub async fn recv(&mut self) -> Result<Option<InternalOnebotEvent>, OneBotApiError> {
-        if let Some(msg) = self.ws.next().await {
-            match msg {
+        while let Some(msg) = self.ws.next().await {
+            return match msg {
                 Ok(tungstenite::Message::Text(text)) => {
                     serde_json::from_str::<InternalOnebotEvent>(&text)
                         .map(Some)
                         .map_err(OneBotApiError::Json)
                 }
+                // 心跳帧由 WebSocket 自动处理
+                Ok(tungstenite::Message::Ping(_) | tungstenite::Message::Pong(_)) => continue,
+                Ok(tungstenite::Message::Close(_)) => Ok(None),
                 Ok(_) => Err(OneBotApiError::InvalidMessage),
                 Err(e) => Err(OneBotApiError::WebSocket(e)),
-            }
-        } else {
-            Ok(None)
+            };
         }
+        Ok(None)
     }
-} 
\ No newline at end of file
+} 
@@ -1,6 +1,6 @@
 #![allow(unused)]
 pub mod response {
-    use crate::internal::message::InternalSegment;
+    use crate::internal::message::{InternalSegment, deserialize_segments};
     use serde::{Deserialize, Serialize};
 
     #[derive(Debug, Serialize, Deserialize)]
@@ -45,6 +45,7 @@ pub mod response {
         /// 发送者信息
         pub sender: SenderInfo,
         /// 消息内容（已解析的消息段）
+        #[serde(deserialize_with = "deserialize_segments")]
         pub message: Vec<InternalSegment>,
     }
 
@@ -157,6 +158,23 @@ pub mod response {
 
     #[derive(Debug, Serialize, Deserialize)]
     pub struct ForwardIdResponse(pub String);
+
+    /// 合并转发消息发送响应数据
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ForwardMessageResponse {
+        /// 消息ID
+        pub message_id: Option<i32>,
+        /// 转发ID（NapCat 中为 `res_id`，部分实现不返回）
+        #[serde(alias = "res_id")]
+        pub forward_id: Option<String>,
+    }
+
+    /// 上传文件响应数据（部分实现不返回数据）
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct UploadFileResponse {
+        /// 文件ID
+        pub file_id: Option<String>,
+    }
 }
 
 pub mod request {
@@ -189,7 +207,7 @@ pub mod request {
             Self { echo, kind }
         }
     }
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     #[serde(tag = "action", content = "params")]
     pub enum ApiRequestKind {
         #[serde(rename = "send_private_msg")]
@@ -204,6 +222,8 @@ pub mod request {
         SetGroupKick(SetGroupKickParams),
         #[serde(rename = "set_group_ban")]
         SetGroupBan(SetGroupBanParams),
+        #[serde(rename = "set_group_anonymous_ban")]
+        SetGroupAnonymousBan(SetGroupAnonymousBanParams),
         #[serde(rename = "set_group_admin")]
         SetGroupAdmin(SetGroupAdminParams),
         #[serde(rename = "set_group_card")]
@@ -224,10 +244,20 @@ pub mod request {
         GetGroupMemberList(GetGroupMemberListParams),
         #[serde(rename = "send_forward_msg")]
         CreateForwardMsg(CreateForwardMsgParams),
+        #[serde(rename = "send_group_forward_msg")]
+        SendGroupForwardMsg(SendGroupForwardMsgParams),
+        #[serde(rename = "send_private_forward_msg")]
+        SendPrivateForwardMsg(SendPrivateForwardMsgParams),
+        #[serde(rename = "get_login_info")]
+        GetLoginInfo(GetLoginInfoParams),
+        #[serde(rename = "upload_group_file")]
+        UploadGroupFile(UploadGroupFileParams),
+        #[serde(rename = "upload_private_file")]
+        UploadPrivateFile(UploadPrivateFileParams),
     }
 
     /// 发送私聊消息参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct SendPrivateMsgParams {
         user_id: String,
         message: SVec<InternalSegment>,
@@ -250,7 +280,7 @@ pub mod request {
     }
 
     /// 发送群消息参数（结构同私聊）
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct SendGroupMsgParams {
         group_id: String,
         message: SVec<InternalSegment>,
@@ -278,7 +308,7 @@ pub mod request {
     }
 
     /// 消息撤回参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct DeleteMsgParams {
         message_id: String,
     }
@@ -301,7 +331,7 @@ pub mod request {
     }
 
     /// 获取消息参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct GetMsgParams {
         message_id: String,
     }
@@ -324,7 +354,7 @@ pub mod request {
     }
 
     /// 群组踢人参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct SetGroupKickParams {
         group_id: String,
         user_id: String,
@@ -353,7 +383,7 @@ pub mod request {
     }
 
     /// 群组禁言参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct SetGroupBanParams {
         group_id: String,
         user_id: String,
@@ -381,8 +411,37 @@ pub mod request {
         }
     }
 
+    /// 群组匿名用户禁言参数
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+    pub struct SetGroupAnonymousBanParams {
+        group_id: String,
+        anonymous_flag: String,
+        duration: u32,
+    }
+    impl OneBotRequest for SetGroupAnonymousBanParams {
+        type RESPONSE = ();
+        fn into_kind(self) -> ApiRequestKind {
+            self.into()
+        }
+    }
+    impl SetGroupAnonymousBanParams {
+        /// 创建群组匿名用户禁言参数
+        ///
+        /// # 参数
+        /// - `group_id`: 目标群号
+        /// - `anonymous_flag`: 匿名用户的 flag
+        /// - `duration`: 禁言时长（秒）
+        pub fn new(group_id: Channel, anonymous_flag: String, duration: u32) -> Self {
+            Self {
+                group_id: group_id.id().to_string(),
+                anonymous_flag,
+                duration,
+            }
+        }
+    }
+
     /// 设置管理员参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct SetGroupAdminParams {
         group_id: String,
         user_id: String,
@@ -411,7 +470,7 @@ pub mod request {
     }
 
     /// 群名片设置参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct SetGroupCardParams {
         group_id: String,
         user_id: String,
@@ -440,7 +499,7 @@ pub mod request {
     }
 
     /// 退群参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct SetGroupLeaveParams {
         group_id: String,
         is_dismiss: bool,
@@ -457,16 +516,16 @@ pub mod request {
         /// # 参数
         /// - `group_id`: 目标群号
         /// - `is_dismiss`: 是否解散群
-        pub fn new(group_id: Channel, is_dismiss: bool) -> Result<Self, ParseIntError> {
-            Ok(Self {
+        pub fn new(group_id: Channel, is_dismiss: bool) -> Self {
+            Self {
                 group_id: group_id.id().to_string(),
                 is_dismiss,
-            })
+            }
         }
     }
 
     /// 好友请求处理参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct SetFriendAddRequestParams {
         flag: String,
         approve: bool,
@@ -495,7 +554,7 @@ pub mod request {
     }
 
     /// 加群请求处理参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct SetGroupAddRequestParams {
         pub flag: String,
         pub sub_type: String,
@@ -527,7 +586,7 @@ pub mod request {
     }
 
     /// 陌生人信息查询参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct GetStrangerInfoParams {
         user_id: i64,
         no_cache: bool,
@@ -553,7 +612,7 @@ pub mod request {
     }
 
     /// 群信息查询参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct GetGroupInfoParams {
         group_id: String,
         no_cache: bool,
@@ -579,7 +638,7 @@ pub mod request {
     }
 
     /// 群成员信息查询参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct GetGroupMemberInfoParams {
         group_id: String,
         user_id: String,
@@ -612,7 +671,7 @@ pub mod request {
     }
 
     /// 群成员列表查询参数
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct GetGroupMemberListParams {
         group_id: String,
     }
@@ -634,7 +693,7 @@ pub mod request {
         }
     }
 
-    #[derive(Debug, Serialize, Deserialize, ProcedureCall)]
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
     pub struct CreateForwardMsgParams {
         messages: Vec<InternalForwardMessage>,
     }
@@ -650,6 +709,135 @@ pub mod request {
         }
     }
 
+    /// 发送群合并转发消息参数
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+    pub struct SendGroupForwardMsgParams {
+        group_id: String,
+        messages: Vec<InternalForwardMessage>,
+    }
+    impl OneBotRequest for SendGroupForwardMsgParams {
+        type RESPONSE = ForwardMessageResponse;
+        fn into_kind(self) -> ApiRequestKind {
+            self.into()
+        }
+    }
+    impl SendGroupForwardMsgParams {
+        /// 创建发送群合并转发消息参数
+        ///
+        /// # 参数
+        /// - `group_id`: 目标群号
+        /// - `messages`: 转发节点
+        pub fn new(group_id: Channel, messages: Vec<InternalForwardMessage>) -> Self {
+            Self {
+                group_id: group_id.id().to_string(),
+                messages,
+            }
+        }
+    }
+
+    /// 发送私聊合并转发消息参数
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+    pub struct SendPrivateForwardMsgParams {
+        user_id: String,
+        messages: Vec<InternalForwardMessage>,
+    }
+    impl OneBotRequest for SendPrivateForwardMsgParams {
+        type RESPONSE = ForwardMessageResponse;
+        fn into_kind(self) -> ApiRequestKind {
+            self.into()
+        }
+    }
+    impl SendPrivateForwardMsgParams {
+        /// 创建发送私聊合并转发消息参数
+        ///
+        /// # 参数
+        /// - `user_id`: 目标QQ号
+        /// - `messages`: 转发节点
+        pub fn new(user_id: Channel, messages: Vec<InternalForwardMessage>) -> Self {
+            Self {
+                user_id: user_id.id().to_string(),
+                messages,
+            }
+        }
+    }
+
+    /// 获取登录信息参数（无参数）
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+    pub struct GetLoginInfoParams {}
+    impl OneBotRequest for GetLoginInfoParams {
+        type RESPONSE = LoginInfo;
+        fn into_kind(self) -> ApiRequestKind {
+            self.into()
+        }
+    }
+    impl GetLoginInfoParams {
+        pub fn new() -> Self {
+            Self {}
+        }
+    }
+
+    /// 上传群文件参数
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+    pub struct UploadGroupFileParams {
+        group_id: String,
+        file: String,
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        folder: Option<String>,
+    }
+    impl OneBotRequest for UploadGroupFileParams {
+        type RESPONSE = Option<UploadFileResponse>;
+        fn into_kind(self) -> ApiRequestKind {
+            self.into()
+        }
+    }
+    impl UploadGroupFileParams {
+        /// 创建上传群文件参数
+        ///
+        /// # 参数
+        /// - `group_id`: 目标群号
+        /// - `file`: 本地文件路径
+        /// - `name`: 储存名称
+        /// - `folder`: 父目录ID（为空时上传到根目录）
+        pub fn new(group_id: Channel, file: String, name: String, folder: Option<String>) -> Self {
+            Self {
+                group_id: group_id.id().to_string(),
+                file,
+                name,
+                folder,
+            }
+        }
+    }
+
+    /// 上传私聊文件参数
+    #[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+    pub struct UploadPrivateFileParams {
+        user_id: String,
+        file: String,
+        name: String,
+    }
+    impl OneBotRequest for UploadPrivateFileParams {
+        type RESPONSE = Option<UploadFileResponse>;
+        fn into_kind(self) -> ApiRequestKind {
+            self.into()
+        }
+    }
+    impl UploadPrivateFileParams {
+        /// 创建上传私聊文件参数
+        ///
+        /// # 参数
+        /// - `user_id`: 对方QQ号
+        /// - `file`: 本地文件路径
+        /// - `name`: 文件名称
+        pub fn new(user_id: Channel, file: String, name: String) -> Self {
+            Self {
+                user_id: user_id.id().to_string(),
+                file,
+                name,
+            }
+        }
+    }
+
     impl From<SendPrivateMsgParams> for ApiRequestKind {
         fn from(value: SendPrivateMsgParams) -> Self {
             Self::SendPrivateMsg(value)
@@ -686,6 +874,12 @@ pub mod request {
         }
     }
 
+    impl From<SetGroupAnonymousBanParams> for ApiRequestKind {
+        fn from(value: SetGroupAnonymousBanParams) -> Self {
+            Self::SetGroupAnonymousBan(value)
+        }
+    }
+
     impl From<SetGroupAdminParams> for ApiRequestKind {
         fn from(value: SetGroupAdminParams) -> Self {
             Self::SetGroupAdmin(value)
@@ -745,4 +939,34 @@ pub mod request {
             Self::CreateForwardMsg(value)
         }
     }
+
+    impl From<SendGroupForwardMsgParams> for ApiRequestKind {
+        fn from(value: SendGroupForwardMsgParams) -> Self {
+            Self::SendGroupForwardMsg(value)
+        }
+    }
+
+    impl From<SendPrivateForwardMsgParams> for ApiRequestKind {
+        fn from(value: SendPrivateForwardMsgParams) -> Self {
+            Self::SendPrivateForwardMsg(value)
+        }
+    }
+
+    impl From<GetLoginInfoParams> for ApiRequestKind {
+        fn from(value: GetLoginInfoParams) -> Self {
+            Self::GetLoginInfo(value)
+        }
+    }
+
+    impl From<UploadGroupFileParams> for ApiRequestKind {
+        fn from(value: UploadGroupFileParams) -> Self {
+            Self::UploadGroupFile(value)
+        }
+    }
+
+    impl From<UploadPrivateFileParams> for ApiRequestKind {
+        fn from(value: UploadPrivateFileParams) -> Self {
+            Self::UploadPrivateFile(value)
+        }
+    }
 }
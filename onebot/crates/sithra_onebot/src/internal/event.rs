@@ -2,7 +2,10 @@ use ioevent::Event;
 use serde::{Deserialize, Serialize};
 use sithra_common::model::SVec;
 
-use super::message::InternalSegment;
+use super::{
+    api::response::StatusInfo,
+    message::{InternalSegment, deserialize_segments},
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InternalOnebotEvent {
@@ -42,6 +45,7 @@ pub struct InternalPrivateMessage {
     pub sub_type: String,
     pub message_id: i32,
     pub user_id: u64,
+    #[serde(deserialize_with = "deserialize_segments")]
     pub message: SVec<InternalSegment>,
     pub raw_message: String,
     pub font: i32,
@@ -63,6 +67,7 @@ pub struct InternalGroupMessage {
     pub group_id: u64,
     pub user_id: u64,
     pub anonymous: Option<InternalAnonymous>,
+    #[serde(deserialize_with = "deserialize_segments")]
     pub message: SVec<InternalSegment>,
     pub raw_message: String,
     pub font: i32,
@@ -185,7 +190,8 @@ pub struct InternalFriendRecallNotice {
 pub enum NotifyEvent {
     #[serde(rename = "poke")]
     Poke {
-        group_id: u64,
+        // 好友戳一戳没有群号
+        group_id: Option<u64>,
         user_id: u64,
         target_id: u64,
     },
@@ -235,9 +241,21 @@ pub struct InternalGroupRequest {
 #[serde(tag = "meta_event_type")]
 pub enum InternalMetaEvent {
     #[serde(rename = "lifecycle")]
-    Lifecycle { sub_type: String },
+    Lifecycle { sub_type: InternalLifecycle },
     #[serde(rename = "heartbeat")]
-    // TODO: 其他未知元事件类型
+    Heartbeat {
+        status: StatusInfo,
+        /// 到下次心跳的间隔（毫秒）
+        interval: u64,
+    },
     #[serde(other)]
     Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InternalLifecycle {
+    Enable,
+    Disable,
+    Connect,
 }
\ No newline at end of file
@@ -1,7 +1,9 @@
 #![allow(unused)]
 
-use serde::{Deserialize, Serialize};
-use sithra_onebot_common::message::*;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::str::FromStr;
+use serde_json::Value;
+use sithra_onebot_common::{cq::*, message::*};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type", content = "data")]
@@ -9,9 +11,15 @@ pub enum InternalSegment {
     #[serde(rename = "text")]
     Text(TextData),
     #[serde(rename = "image")]
-    Image(MediaData),
+    Image(ImageData),
+    #[serde(rename = "face")]
+    Face(FaceData),
     #[serde(rename = "record")]
     Record(MediaData),
+    #[serde(rename = "video")]
+    Video(FileData),
+    #[serde(rename = "file")]
+    File(FileData),
     #[serde(rename = "at")]
     At(AtData),
     #[serde(rename = "poke")]
@@ -26,8 +34,29 @@ pub enum InternalSegment {
     Reply(ReplyData),
     #[serde(rename = "forward")]
     Forward(ForwardData),
+    #[serde(rename = "json")]
+    Json(CardData),
+    #[serde(rename = "xml")]
+    Xml(CardData),
+    #[serde(rename = "music")]
+    Music(MusicData),
+    #[serde(rename = "dice")]
+    Dice(ResultData),
+    #[serde(rename = "rps")]
+    Rps(ResultData),
+    #[serde(rename = "shake")]
+    Shake(EmptyData),
+    /// 未建模的消息段，原样保留类型与参数
     #[serde(untagged)]
-    Unknown,
+    Unknown(UnknownData),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UnknownData {
+    #[serde(rename = "type")]
+    pub r#type: String,
+    #[serde(default)]
+    pub data: serde_json::Map<String, Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -40,6 +69,153 @@ pub struct MediaData {
     pub file: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImageData {
+    #[serde(default)]
+    pub file: String,
+    /// 为 `flash` 时表示闪照
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub image_type: Option<String>,
+    /// 图片子类型，`1` 为表情包
+    #[serde(
+        default,
+        alias = "subType",
+        deserialize_with = "deserialize_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sub_type: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cache: Option<u8>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub proxy: Option<u8>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub timeout: Option<u64>,
+}
+
+impl From<ImageData> for ImageInfo {
+    fn from(value: ImageData) -> Self {
+        let file = if value.file.is_empty() {
+            value.url.clone().unwrap_or_default()
+        } else {
+            value.file
+        };
+        ImageInfo {
+            file,
+            url: value.url,
+            flash: value.image_type.as_deref() == Some("flash"),
+            sub_type: value.sub_type,
+            summary: value.summary,
+            cache: value.cache.map(|cache| cache != 0),
+            proxy: value.proxy.map(|proxy| proxy != 0),
+            timeout: value.timeout,
+        }
+    }
+}
+
+impl From<ImageInfo> for ImageData {
+    fn from(value: ImageInfo) -> Self {
+        ImageData {
+            file: value.file,
+            image_type: value.flash.then(|| "flash".to_string()),
+            sub_type: value.sub_type,
+            url: value.url,
+            summary: value.summary,
+            cache: value.cache.map(u8::from),
+            proxy: value.proxy.map(u8::from),
+            timeout: value.timeout,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileData {
+    #[serde(default)]
+    pub file: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(
+        default,
+        alias = "size",
+        deserialize_with = "deserialize_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub file_size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<String>,
+}
+
+/// 数值参数可能以数字或字符串上报
+fn deserialize_number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Number<T> {
+        Number(T),
+        String(String),
+    }
+    Ok(match Option::<Number<T>>::deserialize(deserializer)? {
+        Some(Number::Number(number)) => Some(number),
+        Some(Number::String(number)) => number.parse().ok(),
+        None => None,
+    })
+}
+
+impl From<FileData> for FileInfo {
+    fn from(value: FileData) -> Self {
+        // 部分实现接收到的视频只给出 `url`
+        let file = if value.file.is_empty() {
+            value.url.clone().unwrap_or_default()
+        } else {
+            value.file
+        };
+        FileInfo {
+            file,
+            url: value.url,
+            name: value.name,
+            size: value.file_size,
+            file_id: value.file_id,
+        }
+    }
+}
+
+impl From<FileInfo> for FileData {
+    fn from(value: FileInfo) -> Self {
+        FileData {
+            file: value.file,
+            url: value.url,
+            name: value.name,
+            file_size: value.size,
+            file_id: value.file_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FaceData {
+    pub id: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AtData {
     pub id: Option<String>,
@@ -56,6 +232,11 @@ pub struct PokeData {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ShareData {
     pub url: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -88,6 +269,91 @@ pub struct ForwardData {
     pub id: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CardData {
+    pub data: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MusicData {
+    /// 平台（`qq`、`163`、`xm`）或 `custom`
+    #[serde(rename = "type")]
+    pub music_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+
+impl From<Music> for MusicData {
+    fn from(value: Music) -> Self {
+        match value {
+            Music::Platform { platform, id } => MusicData {
+                music_type: platform,
+                id: Some(id),
+                url: None,
+                audio: None,
+                title: None,
+                content: None,
+                image: None,
+            },
+            Music::Custom {
+                url,
+                audio,
+                title,
+                content,
+                image,
+            } => MusicData {
+                music_type: "custom".to_string(),
+                id: None,
+                url: Some(url),
+                audio: Some(audio),
+                title: Some(title),
+                content,
+                image,
+            },
+        }
+    }
+}
+
+impl MusicData {
+    /// 缺少必需参数时返回 `None`
+    fn to_music(&self) -> Option<Music> {
+        Music::from_params(|key| match key {
+            "type" => Some(self.music_type.clone()),
+            "id" => self.id.clone(),
+            "url" => self.url.clone(),
+            "audio" => self.audio.clone(),
+            "title" => self.title.clone(),
+            "content" => self.content.clone(),
+            "image" => self.image.clone(),
+            _ => None,
+        })
+    }
+}
+
+/// 掷骰子、猜拳的结果，发送时为空
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResultData {
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub result: Option<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmptyData {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InternalForwardMessage {
     #[serde(rename = "type")]
@@ -96,40 +362,52 @@ pub struct InternalForwardMessage {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct InternalForwardMessageData {
-    pub user_id: String,
-    pub nickname: String,
-    pub content: Vec<InternalSegment>,
+#[serde(untagged)]
+pub enum InternalForwardMessageData {
+    /// 自定义消息
+    Custom {
+        user_id: String,
+        nickname: String,
+        content: Vec<InternalSegment>,
+    },
+    /// 引用已有消息
+    Reference { id: String },
 }
 
 impl InternalForwardMessage {
-    pub fn new(user_id: u64, nickname: String, content: Vec<InternalSegment>) -> Self {
+    pub fn new<U: ToString>(user_id: U, nickname: String, content: Vec<InternalSegment>) -> Self {
         Self {
             r#type: "node".to_string(),
-            data: InternalForwardMessageData {
+            data: InternalForwardMessageData::Custom {
                 user_id: user_id.to_string(),
                 nickname,
                 content,
             },
         }
     }
+
+    pub fn reference(id: String) -> Self {
+        Self {
+            r#type: "node".to_string(),
+            data: InternalForwardMessageData::Reference { id },
+        }
+    }
 }
 
 impl From<InternalSegment> for OneBotSegment {
     fn from(value: InternalSegment) -> Self {
         match value {
             InternalSegment::Text(data) => OneBotSegment::text(data.text),
-            InternalSegment::Image(data) => OneBotSegment::img(data.file),
+            InternalSegment::Image(data) => OneBotSegment::Image(data.into()),
+            InternalSegment::Face(data) => OneBotSegment::face(data.id),
             InternalSegment::Record(data) => OneBotSegment::record(data.file),
-            InternalSegment::At(data) => {
-                if let Some(id) = data.id {
-                    OneBotSegment::at(id.as_str())
-                } else if let Some(qq) = data.qq {
-                    OneBotSegment::at(qq.as_str())
-                } else {
-                    OneBotSegment::text("[@]")
-                }
-            }
+            InternalSegment::Video(data) => OneBotSegment::Video(data.into()),
+            InternalSegment::File(data) => OneBotSegment::File(data.into()),
+            InternalSegment::At(data) => match data.id.or(data.qq) {
+                Some(id) if id == "all" => OneBotSegment::AtAll,
+                Some(id) => OneBotSegment::at(id.as_str()),
+                None => OneBotSegment::text("[@]"),
+            },
             InternalSegment::Poke(data) => OneBotSegment::poke(data.id.as_str()),
             InternalSegment::Location(data) => {
                 let lat = data.lat.parse().unwrap_or(0.0);
@@ -138,21 +416,55 @@ impl From<InternalSegment> for OneBotSegment {
             }
             InternalSegment::Reply(data) => OneBotSegment::reply(data.id.as_str()),
             InternalSegment::Forward(data) => OneBotSegment::forward(ForwardId::new(data.id)),
-            _ => OneBotSegment::Unknown,
+            InternalSegment::Share(data) => OneBotSegment::Share {
+                url: data.url,
+                title: data.title,
+                content: data.content,
+                image: data.image,
+            },
+            InternalSegment::Contact(data) => match data.contact_type {
+                ContactType::QQ => OneBotSegment::contact_user(data.id),
+                ContactType::Group => OneBotSegment::contact_group(data.id),
+            },
+            InternalSegment::Json(data) => OneBotSegment::json(data.data),
+            InternalSegment::Music(data) => match data.to_music() {
+                Some(music) => OneBotSegment::Music(music),
+                None => passthrough(InternalSegment::Music(data)),
+            },
+            InternalSegment::Dice(data) => OneBotSegment::Dice(data.result),
+            InternalSegment::Rps(data) => OneBotSegment::Rps(data.result),
+            InternalSegment::Shake(_) => OneBotSegment::Shake,
+            InternalSegment::Xml(data) => OneBotSegment::xml(data.data),
+            InternalSegment::Unknown(data) => OneBotSegment::unknown(data.r#type, data.data),
         }
     }
 }
 
+/// 无法转为 `OneBotSegment` 的消息段（如缺少参数的音乐分享）原样透传
+fn passthrough(segment: InternalSegment) -> OneBotSegment {
+    match serde_json::from_value(serde_json::to_value(segment).unwrap()) {
+        Ok(UnknownData { r#type, data }) => OneBotSegment::unknown(r#type, data),
+        Err(_) => OneBotSegment::text(""),
+    }
+}
+
 impl From<OneBotSegment> for InternalSegment {
     fn from(value: OneBotSegment) -> Self {
         match value {
             OneBotSegment::Text(text) => InternalSegment::Text(TextData { text }),
-            OneBotSegment::Image(url) => InternalSegment::Image(MediaData { file: url }),
+            OneBotSegment::Image(info) => InternalSegment::Image(info.into()),
+            OneBotSegment::Face(id) => InternalSegment::Face(FaceData { id }),
             OneBotSegment::At(user_id) => InternalSegment::At(AtData {
                 id: Some(user_id.to_string()),
                 qq: None,
             }),
+            OneBotSegment::AtAll => InternalSegment::At(AtData {
+                id: None,
+                qq: Some("all".to_string()),
+            }),
             OneBotSegment::Record(url) => InternalSegment::Record(MediaData { file: url }),
+            OneBotSegment::Video(info) => InternalSegment::Video(info.into()),
+            OneBotSegment::File(info) => InternalSegment::File(info.into()),
             OneBotSegment::Poke(user_id) => InternalSegment::Poke(PokeData {
                 poke_type: "poke".to_string(),
                 id: user_id.to_string(),
@@ -167,8 +479,162 @@ impl From<OneBotSegment> for InternalSegment {
             OneBotSegment::Forward(forward_id) => InternalSegment::Forward(ForwardData {
                 id: forward_id.to_string(),
             }),
-            OneBotSegment::Unknown => InternalSegment::Unknown,
+            OneBotSegment::Share {
+                url,
+                title,
+                content,
+                image,
+            } => InternalSegment::Share(ShareData {
+                url,
+                title,
+                content,
+                image,
+            }),
+            OneBotSegment::Contact { kind, id } => InternalSegment::Contact(ContactData {
+                contact_type: match kind {
+                    ContactKind::User => ContactType::QQ,
+                    ContactKind::Group => ContactType::Group,
+                },
+                id,
+            }),
+            OneBotSegment::Json(data) => InternalSegment::Json(CardData { data }),
+            OneBotSegment::Xml(data) => InternalSegment::Xml(CardData { data }),
+            OneBotSegment::Music(music) => InternalSegment::Music(music.into()),
+            OneBotSegment::Dice(result) => InternalSegment::Dice(ResultData { result }),
+            OneBotSegment::Rps(result) => InternalSegment::Rps(ResultData { result }),
+            OneBotSegment::Shake => InternalSegment::Shake(EmptyData {}),
+            OneBotSegment::Unknown { r#type, data } => {
+                // 类型已建模时按对应的消息段解析，否则原样透传
+                let segment = serde_json::json!({ "type": r#type, "data": data });
+                serde_json::from_value(segment)
+                    .unwrap_or(InternalSegment::Unknown(UnknownData { r#type, data }))
+            }
         }
     }
 }
 
+impl From<CqSegment> for InternalSegment {
+    fn from(value: CqSegment) -> Self {
+        let data: serde_json::Map<String, Value> = value
+            .data
+            .into_iter()
+            .map(|(key, value)| (key, Value::String(value)))
+            .collect();
+        let segment = serde_json::json!({ "type": value.r#type, "data": data });
+        serde_json::from_value(segment).unwrap_or(InternalSegment::Unknown(UnknownData {
+            r#type: value.r#type,
+            data,
+        }))
+    }
+}
+
+impl From<InternalSegment> for CqSegment {
+    fn from(value: InternalSegment) -> Self {
+        let Ok(Value::Object(mut segment)) = serde_json::to_value(value) else {
+            return CqSegment::text("");
+        };
+        let r#type = match segment.remove("type") {
+            Some(Value::String(r#type)) => r#type,
+            _ => return CqSegment::text(""),
+        };
+        let data = match segment.remove("data") {
+            Some(Value::Object(data)) => data
+                .into_iter()
+                .filter_map(|(key, value)| match value {
+                    Value::Null => None,
+                    Value::String(value) => Some((key, value)),
+                    value => Some((key, value.to_string())),
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        CqSegment::new(r#type, data)
+    }
+}
+
+/// 解析 CQ 码字符串为消息段
+pub fn parse_cq<C: FromIterator<InternalSegment>>(s: &str) -> C {
+    CqCode::parse(s).into_iter().map(Into::into).collect()
+}
+
+/// 将消息段序列化为 CQ 码字符串
+pub fn to_cq_string<I: IntoIterator<Item = InternalSegment>>(segments: I) -> String {
+    segments
+        .into_iter()
+        .map(CqSegment::from)
+        .collect::<CqCode>()
+        .to_string()
+}
+
+/// 反序列化消息内容，兼容数组与 CQ 码字符串（`message_format: string`）两种上报格式
+pub fn deserialize_segments<'de, D, C>(deserializer: D) -> Result<C, D::Error>
+where
+    D: Deserializer<'de>,
+    C: Deserialize<'de> + FromIterator<InternalSegment>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MessageFormat<C> {
+        String(String),
+        Array(C),
+    }
+    Ok(match MessageFormat::<C>::deserialize(deserializer)? {
+        MessageFormat::String(s) => parse_cq(&s),
+        MessageFormat::Array(segments) => segments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cq_string() {
+        let segments: Vec<InternalSegment> = parse_cq("&#91;hi&#93;[CQ:at,qq=123]");
+        match &segments[..] {
+            [InternalSegment::Text(text), InternalSegment::At(at)] => {
+                assert_eq!(text.text, "[hi]");
+                assert_eq!(at.qq.as_deref(), Some("123"));
+            }
+            segments => panic!("unexpected segments: {:?}", segments),
+        }
+        assert_eq!(to_cq_string(segments), "&#91;hi&#93;[CQ:at,qq=123]");
+    }
+
+    #[test]
+    fn deserializes_string_format_message() {
+        #[derive(Deserialize)]
+        struct Payload {
+            #[serde(deserialize_with = "deserialize_segments")]
+            message: Vec<InternalSegment>,
+        }
+        let payload: Payload = serde_json::from_str(
+            r#"{
+                "post_type": "message",
+                "message_type": "private",
+                "message_format": "string",
+                "message": "你好[CQ:face,id=178][CQ:image,file=a.jpg,url=http://x/?a=1&amp;b=2&#44;3]"
+            }"#,
+        )
+        .unwrap();
+        match &payload.message[..] {
+            [
+                InternalSegment::Text(text),
+                InternalSegment::Face(face),
+                InternalSegment::Image(image),
+            ] => {
+                assert_eq!(text.text, "你好");
+                assert_eq!(face.id, "178");
+                assert_eq!(image.file, "a.jpg");
+                assert_eq!(image.url.as_deref(), Some("http://x/?a=1&b=2,3"));
+            }
+            segments => panic!("unexpected segments: {:?}", segments),
+        }
+
+        let payload: Payload = serde_json::from_str(
+            r#"{"message_format": "array", "message": [{"type": "text", "data": {"text": "你好"}}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(&payload.message[..], [InternalSegment::Text(text)] if text.text == "你好"));
+    }
+}
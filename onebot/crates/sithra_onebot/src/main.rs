@@ -1,51 +1,65 @@
 mod api_client;
+mod auth;
+mod bots;
 mod config;
 mod error;
 mod event_client;
 mod internal;
+mod media;
 mod procedure;
+mod reverse_server;
 mod state;
 mod subscribers;
+mod webhook;
 
+use config::ConnectionMode;
 use internal::event::{
-    InternalGroupMessage, InternalMessageEvent, InternalOnebotEventKind, InternalPrivateMessage,
+    InternalGroupMessage, InternalLifecycle, InternalMessageEvent, InternalMetaEvent,
+    InternalNoticeEvent, InternalOnebotEventKind, InternalPrivateMessage, InternalRequestEvent,
+    NotifyEvent,
 };
 use log::*;
 use sithra_common::{kv, prelude::*};
-use sithra_onebot_common::message::{OneBotMessage, OneBotSegment};
+use sithra_onebot_common::{
+    event::*,
+    message::{OneBotMessage, OneBotSegment},
+};
 use state::OneBotAdapterState;
 use std::sync::Arc;
+use std::time::Duration;
 use subscribers::SUBSCRIBERS;
 
+// 连续错过多少次心跳后认为事件连接已失效
+const MAX_MISSED_HEARTBEATS: u32 = 3;
+
 #[derive(Debug, Clone)]
 pub struct OneBotGenericId {
     self_id: String,
 }
 impl OneBotGenericId {
-    pub fn from_config(config: &config::OneBotConfig) -> Self {
+    pub fn new(self_id: impl ToString) -> Self {
         Self {
-            self_id: config.self_id.clone(),
+            self_id: self_id.to_string(),
         }
     }
 }
 impl EnsureGenericId for OneBotGenericId {
     type Error = String;
     fn ensure_generic_id(id: &GenericId) -> Result<Self, Self::Error> {
+        if id.get("type").map(String::as_str) != Some("onebot") {
+            return Err("not a onebot generic id".to_string());
+        }
         let self_id = id.get("self_id").ok_or("self_id not found")?;
         Ok(OneBotGenericId {
             self_id: self_id.to_string(),
         })
     }
+    /// 仅匹配本适配器已连接账号的 GenericId
+    ///
+    /// 同一总线上可能有其他适配器，或其他 onebot 适配器实例服务着别的账号。
     fn match_adapter(id: &GenericId) -> bool {
-        let self_id = id.get("self_id");
-        let ty = id.get("type");
-        if self_id != Some(&"onebot".to_string()) {
-            return false;
-        }
-        if ty != Some(&"onebot".to_string()) {
-            return false;
-        }
-        true
+        let is_onebot = id.get("type").map(String::as_str) == Some("onebot");
+        is_onebot && id.get("self_id").is_some_and(|self_id| bots::contains(self_id))
     }
 }
 impl From<OneBotGenericId> for GenericId {
@@ -77,15 +91,14 @@ fn build_user(user_id: u64, nickname: Option<String>, card: Option<String>) -> U
 async fn handle_message(
     wright: &ioevent::EffectWright,
     generic_id: &OneBotGenericId,
-    message_id: i32,
-    segments: SVec<internal::message::InternalSegment>,
+    msg: OneBotMessage,
     user_id: u64,
     nickname: Option<String>,
     card: Option<String>,
     channel_id: u64,
     channel_type: ChannelType,
 ) -> Result<(), error::OneBotApiError> {
-    let msg = build_onebot_message(message_id, segments);
+    debug!("收到消息 [{}]: {}", user_id, msg.to_cq_string());
     let channel = Channel::new(channel_id, channel_type);
     let user = build_user(user_id, nickname, card);
     let event = MessageEvent::new(generic_id.clone(), channel, user, msg);
@@ -100,11 +113,11 @@ async fn handle_private_message(
     generic_id: &OneBotGenericId,
     message: InternalPrivateMessage,
 ) -> Result<(), error::OneBotApiError> {
+    let msg = build_onebot_message(message.message_id, message.message);
     handle_message(
         wright,
         generic_id,
-        message.message_id,
-        message.message,
+        msg,
         message.user_id,
         message.sender.nickname,
         None,
@@ -119,13 +132,35 @@ async fn handle_group_message(
     generic_id: &OneBotGenericId,
     message: InternalGroupMessage,
 ) -> Result<(), error::OneBotApiError> {
+    let mut nickname = message.sender.nickname;
+    // 匿名消息以匿名名称作为昵称，匿名信息通过单独的事件发出
+    if let Some(anonymous) = message.anonymous {
+        nickname = Some(anonymous.name.clone());
+        // 匿名信息只是附加的事件，发出失败时仍需发出消息本身
+        let emitted = emit(
+            wright,
+            &GroupAnonymousMessageEvent {
+                generic_id: generic_id.clone().into(),
+                channel: Channel::new(message.group_id, ChannelType::Group),
+                message_id: MessageId::new(message.message_id),
+                anonymous: Anonymous {
+                    id: anonymous.id.to_string(),
+                    name: anonymous.name,
+                    flag: anonymous.flag,
+                },
+            },
+        );
+        if let Err(e) = emitted {
+            error!("发出匿名消息事件失败: {}", e);
+        }
+    }
+    let msg = build_onebot_message(message.message_id, message.message);
     handle_message(
         wright,
         generic_id,
-        message.message_id,
-        message.message,
+        msg,
         message.user_id,
-        message.sender.nickname,
+        nickname,
         message.sender.card,
         message.group_id,
         ChannelType::Group,
@@ -133,15 +168,280 @@ async fn handle_group_message(
     .await
 }
 
+fn emit<E: ioevent::Event>(
+    wright: &ioevent::EffectWright,
+    event: &E,
+) -> Result<(), error::OneBotApiError> {
+    wright
+        .emit(event)
+        .map_err(|e| error::OneBotApiError::Internal(e.to_string()))
+}
+
+async fn handle_notice(
+    wright: &ioevent::EffectWright,
+    generic_id: &OneBotGenericId,
+    notice: InternalNoticeEvent,
+) -> Result<(), error::OneBotApiError> {
+    let generic_id: GenericId = generic_id.clone().into();
+    let group = |group_id: u64| Channel::new(group_id, ChannelType::Group);
+    match notice {
+        InternalNoticeEvent::GroupIncrease(notice) => emit(
+            wright,
+            &GroupIncreaseEvent {
+                generic_id,
+                channel: group(notice.group_id),
+                user_id: UserId::new(notice.user_id),
+                operator_id: UserId::new(notice.operator_id),
+                sub_type: notice.sub_type,
+            },
+        ),
+        InternalNoticeEvent::GroupDecrease(notice) => emit(
+            wright,
+            &GroupDecreaseEvent {
+                generic_id,
+                channel: group(notice.group_id),
+                user_id: UserId::new(notice.user_id),
+                operator_id: UserId::new(notice.operator_id),
+                sub_type: notice.sub_type,
+            },
+        ),
+        InternalNoticeEvent::GroupBan(notice) => emit(
+            wright,
+            &GroupBanEvent {
+                generic_id,
+                channel: group(notice.group_id),
+                user_id: UserId::new(notice.user_id),
+                operator_id: UserId::new(notice.operator_id),
+                duration: notice.duration,
+                sub_type: notice.sub_type,
+            },
+        ),
+        InternalNoticeEvent::GroupAdmin(notice) => emit(
+            wright,
+            &GroupAdminEvent {
+                generic_id,
+                channel: group(notice.group_id),
+                user_id: UserId::new(notice.user_id),
+                sub_type: notice.sub_type,
+            },
+        ),
+        InternalNoticeEvent::GroupRecall(notice) => emit(
+            wright,
+            &GroupRecallEvent {
+                generic_id,
+                channel: group(notice.group_id),
+                user_id: UserId::new(notice.user_id),
+                operator_id: UserId::new(notice.operator_id),
+                message_id: MessageId::new(notice.message_id),
+            },
+        ),
+        InternalNoticeEvent::FriendRecall(notice) => emit(
+            wright,
+            &FriendRecallEvent {
+                generic_id,
+                channel: Channel::new(notice.user_id, ChannelType::Private),
+                user_id: UserId::new(notice.user_id),
+                message_id: MessageId::new(notice.message_id),
+            },
+        ),
+        InternalNoticeEvent::FriendAdd(notice) => emit(
+            wright,
+            &FriendAddEvent {
+                generic_id,
+                user_id: UserId::new(notice.user_id),
+            },
+        ),
+        InternalNoticeEvent::GroupUpload(notice) => emit(
+            wright,
+            &GroupUploadEvent {
+                generic_id,
+                channel: group(notice.group_id),
+                user_id: UserId::new(notice.user_id),
+                file: GroupFile {
+                    id: notice.file.id,
+                    name: notice.file.name,
+                    size: notice.file.size,
+                    busid: notice.file.busid,
+                },
+            },
+        ),
+        InternalNoticeEvent::Notify(notify) => handle_notify(wright, generic_id, notify),
+        InternalNoticeEvent::Unknown => Ok(()),
+    }
+}
+
+fn handle_notify(
+    wright: &ioevent::EffectWright,
+    generic_id: GenericId,
+    notify: NotifyEvent,
+) -> Result<(), error::OneBotApiError> {
+    let group = |group_id: u64| Channel::new(group_id, ChannelType::Group);
+    match notify {
+        NotifyEvent::Poke {
+            group_id,
+            user_id,
+            target_id,
+        } => {
+            let channel = match group_id {
+                Some(group_id) => group(group_id),
+                None => Channel::new(user_id, ChannelType::Private),
+            };
+            emit(
+                wright,
+                &PokeEvent {
+                    generic_id,
+                    channel,
+                    user: build_user(user_id, None, None),
+                    target_id: UserId::new(target_id),
+                },
+            )
+        }
+        NotifyEvent::LuckyKing {
+            group_id,
+            user_id,
+            target_id,
+        } => emit(
+            wright,
+            &LuckyKingEvent {
+                generic_id,
+                channel: group(group_id),
+                user: build_user(user_id, None, None),
+                target_id: UserId::new(target_id),
+            },
+        ),
+        NotifyEvent::Honor {
+            group_id,
+            honor_type,
+            user_id,
+        } => emit(
+            wright,
+            &HonorEvent {
+                generic_id,
+                channel: group(group_id),
+                user: build_user(user_id, None, None),
+                honor_type,
+            },
+        ),
+        NotifyEvent::Unknown => Ok(()),
+    }
+}
+
+async fn handle_request(
+    wright: &ioevent::EffectWright,
+    generic_id: &OneBotGenericId,
+    request: InternalRequestEvent,
+) -> Result<(), error::OneBotApiError> {
+    let generic_id: GenericId = generic_id.clone().into();
+    match request {
+        InternalRequestEvent::Friend(request) => emit(
+            wright,
+            &FriendRequestEvent {
+                generic_id,
+                user_id: UserId::new(request.user_id),
+                comment: request.comment,
+                flag: request.flag,
+            },
+        ),
+        InternalRequestEvent::Group(request) => emit(
+            wright,
+            &GroupRequestEvent {
+                generic_id,
+                channel: Channel::new(request.group_id, ChannelType::Group),
+                user_id: UserId::new(request.user_id),
+                comment: request.comment,
+                flag: request.flag,
+                sub_type: request.sub_type,
+            },
+        ),
+    }
+}
+
+/// 处理元事件，返回新的心跳超时时间
+fn handle_meta(meta: &InternalMetaEvent) -> Option<Duration> {
+    match meta {
+        InternalMetaEvent::Lifecycle { sub_type } => {
+            match sub_type {
+                InternalLifecycle::Enable => info!("OneBot 已启用"),
+                InternalLifecycle::Disable => warn!("OneBot 已停用"),
+                InternalLifecycle::Connect => info!("OneBot 事件连接已建立"),
+            }
+            None
+        }
+        InternalMetaEvent::Heartbeat { status, interval } => {
+            if !status.good || status.online == Some(false) {
+                warn!("OneBot 状态异常: {:?}", status);
+            }
+            // 间隔为 0 时无法判断是否错过心跳，不做检测
+            Some(Duration::from_millis(*interval) * MAX_MISSED_HEARTBEATS)
+                .filter(|timeout| !timeout.is_zero())
+        }
+        InternalMetaEvent::Unknown => None,
+    }
+}
+
+/// 处理事件连接上的事件，直到连接断开
+///
+/// `watchdog` 为 `true` 时，连续多次未收到心跳会放弃该连接以触发重连；
+/// HTTP POST 上报没有可重建的连接，不做检测。
 async fn process_events(
     mut event_client: event_client::OneBotEventClient,
     wright: Arc<ioevent::EffectWright>,
-    generic_id: Arc<OneBotGenericId>,
+    watchdog: bool,
 ) {
-    while let Ok(Some(event)) = event_client.recv().await {
+    // 收到第一个心跳前不做超时检测
+    let mut heartbeat_timeout = None;
+    loop {
+        let event = match heartbeat_timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, event_client.recv()).await {
+                Ok(event) => event,
+                Err(_) => {
+                    warn!("连续 {} 次未收到心跳，事件连接可能已失效", MAX_MISSED_HEARTBEATS);
+                    break;
+                }
+            },
+            None => event_client.recv().await,
+        };
+        let event = match event {
+            Ok(Some(event)) => event,
+            Ok(None) => break,
+            Err(error::OneBotApiError::Json(e)) => {
+                error!("解析事件失败: {}", e);
+                continue;
+            }
+            Err(e) => {
+                error!("接收事件失败: {}", e);
+                break;
+            }
+        };
+        if let InternalOnebotEventKind::Meta(meta) = &event.kind {
+            if let Some(timeout) = handle_meta(meta).filter(|_| watchdog) {
+                heartbeat_timeout = Some(timeout);
+            }
+            continue;
+        }
+
+        let self_id = event.self_id.to_string();
+        if bots::is_rejected(&self_id) {
+            warn!("收到已拒绝账号 {} 的事件，已忽略", self_id);
+            continue;
+        }
+        match event_client.login().self_id() {
+            Some(login_id) if login_id != self_id => {
+                warn!(
+                    "事件连接登录的账号为 {}，却收到了账号 {} 的事件，已忽略",
+                    login_id, self_id
+                );
+                continue;
+            }
+            Some(_) => {}
+            // 尚未获取到登录信息，先放行，避免丢失启动阶段的事件；
+            // HTTP POST 上报的 API 可能不可达，以该事件的账号登记
+            None => event_client.login().claim(&self_id),
+        }
+
         let event_kind = event.kind;
         let wright = wright.clone();
-        let generic_id = generic_id.clone();
+        let generic_id = OneBotGenericId::new(self_id);
 
         tokio::spawn(async move {
             match event_kind {
@@ -157,7 +457,17 @@ async fn process_events(
                         }
                     }
                 },
-                _ => {} // TODO: 处理其他事件
+                InternalOnebotEventKind::Notice(notice) => {
+                    if let Err(e) = handle_notice(&wright, &generic_id, notice).await {
+                        error!("处理通知事件失败: {}", e);
+                    }
+                }
+                InternalOnebotEventKind::Request(request) => {
+                    if let Err(e) = handle_request(&wright, &generic_id, request).await {
+                        error!("处理请求事件失败: {}", e);
+                    }
+                }
+                InternalOnebotEventKind::Meta(_) | InternalOnebotEventKind::Unknown(_) => {}
             }
         });
     }
@@ -177,14 +487,41 @@ async fn main(wright: &ioevent::EffectWright) {
         }
     };
 
-    let generic_id = Arc::new(OneBotGenericId::from_config(&config));
     let wright = Arc::new(wright.clone());
+
+    for bot in config.bots() {
+        match bot.mode {
+            // 由 API 一侧建立的事件连接统一在 `run_event_clients` 中处理
+            ConnectionMode::Forward if bot.ws_universal => {}
+            ConnectionMode::Reverse => {}
+            ConnectionMode::Forward => {
+                tokio::spawn(run_forward(bot, wright.clone()));
+            }
+            ConnectionMode::Webhook => {
+                tokio::spawn(run_webhook(bot, wright.clone()));
+            }
+        }
+    }
+    run_event_clients(wright).await;
+}
+
+async fn run_forward(config: config::BotConfig, wright: Arc<ioevent::EffectWright>) {
     let ws_event = join_url(&config.ws_url, "/event");
 
     loop {
         info!("正在连接事件WebSocket...");
-        let event_client = match event_client::OneBotEventClient::new(&ws_event).await {
+        let event_client = match event_client::OneBotEventClient::new(
+            &ws_event,
+            config.access_token(),
+        )
+        .await
+        {
             Ok(client) => client,
+            Err(e @ (error::OneBotApiError::Unauthorized | error::OneBotApiError::Forbidden)) => {
+                error!("连接事件WebSocket失败: {}，请检查 access-token 配置", e);
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                continue;
+            }
             Err(e) => {
                 error!("连接事件WebSocket失败: {}", e);
                 tokio::time::sleep(std::time::Duration::from_secs(5)).await;
@@ -193,7 +530,32 @@ async fn main(wright: &ioevent::EffectWright) {
         };
 
         info!("WebSocket连接成功");
-        process_events(event_client, wright.clone(), generic_id.clone()).await;
+        let event_client = event_client.bind(bots::login(config.index));
+        process_events(event_client, wright.clone(), true).await;
+    }
+}
+
+/// 处理由 API 一侧建立的事件连接（反向 WebSocket、通用连接）
+async fn run_event_clients(wright: Arc<ioevent::EffectWright>) {
+    let Some(mut event_clients) = event_client::take_event_clients() else {
+        error!("事件连接未建立");
+        return;
+    };
+
+    info!("正在等待事件连接...");
+    while let Some(event_client) = event_clients.recv().await {
+        tokio::spawn(process_events(event_client, wright.clone(), true));
+    }
+}
+
+async fn run_webhook(config: config::BotConfig, wright: Arc<ioevent::EffectWright>) {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let event_client =
+        event_client::OneBotEventClient::from_channel(rx).bind(bots::login(config.index));
+    tokio::spawn(process_events(event_client, wright, false));
+
+    if let Err(e) = webhook::serve(&config, tx).await {
+        error!("HTTP事件上报服务端异常退出: {}", e);
     }
 }
 
@@ -204,3 +566,80 @@ pub fn join_url(url: &str, path: &str) -> String {
         format!("{}/{}", url, path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connect_bot(self_id: &str) {
+        let api_client = api_client::OneBotApiClient::http("http://127.0.0.1:5700", None).unwrap();
+        bots::register(self_id.to_string(), api_client);
+    }
+
+    #[test]
+    fn matches_connected_bots() {
+        connect_bot("10001");
+        connect_bot("10002");
+        assert!(OneBotGenericId::match_adapter(&OneBotGenericId::new("10001").into()));
+        assert!(OneBotGenericId::match_adapter(&OneBotGenericId::new("10002").into()));
+    }
+
+    #[test]
+    fn ignores_bots_of_other_onebot_adapters() {
+        connect_bot("20001");
+        let id = GenericId::new(kv! {"type": "onebot", "self_id": "20002"});
+        assert!(!OneBotGenericId::match_adapter(&id));
+    }
+
+    #[test]
+    fn ignores_other_adapter_types() {
+        connect_bot("30001");
+        let id = GenericId::new(kv! {"type": "satori", "self_id": "30001"});
+        assert!(!OneBotGenericId::match_adapter(&id));
+        assert!(OneBotGenericId::ensure_generic_id(&id).is_err());
+    }
+
+    #[test]
+    fn ignores_incomplete_generic_ids() {
+        connect_bot("onebot");
+        let id = GenericId::new(kv! {"self_id": "onebot"});
+        assert!(!OneBotGenericId::match_adapter(&id));
+        let id = GenericId::new(kv! {"type": "onebot"});
+        assert!(!OneBotGenericId::match_adapter(&id));
+    }
+
+    #[test]
+    fn rejected_bots_no_longer_match() {
+        connect_bot("40001");
+        bots::reject("40001".to_string());
+        assert!(!OneBotGenericId::match_adapter(&OneBotGenericId::new("40001").into()));
+    }
+
+    #[test]
+    fn webhook_bots_match_before_login() {
+        let api_client = api_client::OneBotApiClient::http("http://127.0.0.1:5700", None).unwrap();
+        let login = bots::Login::default();
+        login.allow_claim(api_client);
+        login.claim("60001");
+        assert_eq!(login.self_id(), Some("60001"));
+        assert!(OneBotGenericId::match_adapter(&OneBotGenericId::new("60001").into()));
+        // 之后其他账号的事件不会再登记
+        login.claim("60002");
+        assert!(!OneBotGenericId::match_adapter(&OneBotGenericId::new("60002").into()));
+    }
+
+    #[test]
+    fn claim_requires_webhook_login() {
+        let login = bots::Login::default();
+        login.claim("70001");
+        assert_eq!(login.self_id(), None);
+        assert!(!OneBotGenericId::match_adapter(&OneBotGenericId::new("70001").into()));
+    }
+
+    #[test]
+    fn round_trips_through_generic_id() {
+        let id: GenericId = OneBotGenericId::new(50001).into();
+        let id = OneBotGenericId::ensure_generic_id(&id).unwrap();
+        assert_eq!(id.self_id, "50001");
+    }
+}
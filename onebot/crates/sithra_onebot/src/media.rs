@@ -0,0 +1,274 @@
+use crate::{config::OneBotConfig, error::OneBotApiError, internal::message::InternalSegment};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use sithra_onebot_common::message::file_source;
+use std::path::PathBuf;
+use tokio::io::AsyncReadExt;
+
+// 识别 MIME 类型需要的文件头长度
+const HEAD_LEN: usize = 16;
+// HEIC/HEIF 图片在 `ftyp` 中声明的品牌
+const HEIF_BRANDS: [&[u8]; 5] = [b"heic", b"heix", b"hevc", b"mif1", b"msf1"];
+
+/// 发送媒体文件时的限制
+#[derive(Debug, Clone)]
+pub struct MediaConfig {
+    /// 大小上限（字节）
+    pub max_size: u64,
+    /// 是否将本地文件读取为 base64
+    pub inline_local: bool,
+}
+
+impl MediaConfig {
+    pub fn from_config(config: &OneBotConfig) -> Self {
+        Self {
+            max_size: config.media_max_size * 1024 * 1024,
+            inline_local: config.media_inline_local,
+        }
+    }
+}
+
+/// 媒体文件的类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Image,
+    Audio,
+    Video,
+    File,
+}
+
+impl MediaKind {
+    fn of_mime(mime: &str) -> Self {
+        match mime.split_once('/').map(|(kind, _)| kind) {
+            Some("image") => MediaKind::Image,
+            Some("audio") => MediaKind::Audio,
+            Some("video") => MediaKind::Video,
+            _ => MediaKind::File,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            MediaKind::Image => "图片",
+            MediaKind::Audio => "语音",
+            MediaKind::Video => "视频",
+            MediaKind::File => "文件",
+        }
+    }
+}
+
+/// 根据文件头识别 MIME 类型
+pub fn sniff_mime(head: &[u8]) -> Option<&'static str> {
+    let at = |offset: usize, magic: &[u8]| head.get(offset..offset + magic.len()) == Some(magic);
+    let mime = match head {
+        _ if at(0, b"\x89PNG\r\n\x1a\n") => "image/png",
+        _ if at(0, b"\xff\xd8\xff") => "image/jpeg",
+        _ if at(0, b"GIF87a") || at(0, b"GIF89a") => "image/gif",
+        _ if at(0, b"RIFF") && at(8, b"WEBP") => "image/webp",
+        _ if at(0, b"RIFF") && at(8, b"WAVE") => "audio/wav",
+        _ if at(0, b"RIFF") && at(8, b"AVI ") => "video/x-msvideo",
+        _ if at(0, b"BM") => "image/bmp",
+        _ if at(0, b"II*\0") || at(0, b"MM\0*") => "image/tiff",
+        _ if at(0, b"#!AMR") => "audio/amr",
+        _ if at(0, b"#!SILK_V3") || at(1, b"#!SILK_V3") => "audio/silk",
+        _ if at(0, b"ID3") => "audio/mpeg",
+        [0xff, 0xfb | 0xf3 | 0xf2, ..] => "audio/mpeg",
+        _ if at(0, b"OggS") => "audio/ogg",
+        _ if at(0, b"fLaC") => "audio/flac",
+        _ if at(4, b"ftypM4A") => "audio/mp4",
+        // AVIF、HEIC 与 MP4 同为 ISO BMFF 容器，需在 `ftyp` 之前按品牌区分
+        _ if at(4, b"ftypavif") || at(4, b"ftypavis") => "image/avif",
+        _ if at(4, b"ftyp") && HEIF_BRANDS.iter().any(|brand| at(8, brand)) => "image/heic",
+        _ if at(4, b"ftyp") => "video/mp4",
+        _ if at(0, b"\x1a\x45\xdf\xa3") => "video/webm",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+/// 转换消息段中的文件来源，见 [`resolve`]
+pub async fn resolve_segment(
+    segment: InternalSegment,
+    config: &MediaConfig,
+) -> Result<InternalSegment, OneBotApiError> {
+    Ok(match segment {
+        InternalSegment::Image(mut data) => {
+            data.file = resolve(&data.file, MediaKind::Image, config).await?;
+            InternalSegment::Image(data)
+        }
+        InternalSegment::Record(mut data) => {
+            data.file = resolve(&data.file, MediaKind::Audio, config).await?;
+            InternalSegment::Record(data)
+        }
+        InternalSegment::Video(mut data) => {
+            data.file = resolve(&data.file, MediaKind::Video, config).await?;
+            InternalSegment::Video(data)
+        }
+        InternalSegment::File(mut data) => {
+            data.file = resolve(&data.file, MediaKind::File, config).await?;
+            InternalSegment::File(data)
+        }
+        segment => segment,
+    })
+}
+
+/// 将文件来源转换为 OneBot 实现可用的 `file` 参数
+///
+/// - `base64://`：校验大小与文件类型后原样发送
+/// - `file://` 或绝对路径：校验后以 `file://` 发送，开启 `media-inline-local` 时读取为 `base64://`
+/// - 其他（如 URL）：原样发送
+pub async fn resolve(
+    file: &str,
+    kind: MediaKind,
+    config: &MediaConfig,
+) -> Result<String, OneBotApiError> {
+    if let Some(data) = file.strip_prefix("base64://") {
+        check_size(base64_len(data), config)?;
+        let head = BASE64_STANDARD
+            .decode(base64_head(data))
+            .map_err(|_| OneBotApiError::InvalidMedia("base64 数据无效".to_string()))?;
+        check_kind(&head, kind)?;
+        return Ok(file.to_string());
+    }
+
+    let Some(path) = local_path(file) else {
+        return Ok(file.to_string());
+    };
+    let metadata = match tokio::fs::metadata(&path).await {
+        Ok(metadata) => metadata,
+        // 文件可能位于 OneBot 实现所在的机器上，交由实现处理
+        Err(_) if !config.inline_local => return Ok(file_source(&path)),
+        Err(e) => return Err(e.into()),
+    };
+    check_size(metadata.len(), config)?;
+    if config.inline_local {
+        let bytes = tokio::fs::read(&path).await?;
+        check_kind(&bytes, kind)?;
+        return Ok(format!("base64://{}", BASE64_STANDARD.encode(bytes)));
+    }
+    let mut head = Vec::with_capacity(HEAD_LEN);
+    tokio::fs::File::open(&path)
+        .await?
+        .take(HEAD_LEN as u64)
+        .read_to_end(&mut head)
+        .await?;
+    check_kind(&head, kind)?;
+    Ok(file_source(&path))
+}
+
+/// 解析 `file://` URI 或绝对路径
+fn local_path(file: &str) -> Option<PathBuf> {
+    let Some(path) = file.strip_prefix("file://") else {
+        let path = PathBuf::from(file);
+        return path.is_absolute().then_some(path);
+    };
+    // Windows 路径 `file:///C:/...`
+    let path = match path.as_bytes() {
+        [b'/', _, b':', ..] => &path[1..],
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+/// base64 数据解码后的字节数
+fn base64_len(data: &str) -> u64 {
+    (data.trim_end_matches('=').len() as u64) * 3 / 4
+}
+
+/// 编码文件头所需的 base64 前缀
+///
+/// 4 个字符对应 3 个字节，截取到 4 的整数倍以便单独解码。
+fn base64_head(data: &str) -> &str {
+    let head_len = data.len().min(HEAD_LEN / 3 * 4 + 4) / 4 * 4;
+    data.get(..head_len).unwrap_or_default()
+}
+
+fn check_size(size: u64, config: &MediaConfig) -> Result<(), OneBotApiError> {
+    if size > config.max_size {
+        return Err(OneBotApiError::MediaTooLarge {
+            size,
+            limit: config.max_size,
+        });
+    }
+    Ok(())
+}
+
+/// 校验文件类型是否与消息段相符，无法识别的格式（如 SVG）交由 OneBot 实现处理
+fn check_kind(head: &[u8], kind: MediaKind) -> Result<(), OneBotApiError> {
+    if kind == MediaKind::File {
+        return Ok(());
+    }
+    match sniff_mime(head) {
+        Some(mime) if MediaKind::of_mime(mime) != kind => Err(OneBotApiError::InvalidMedia(
+            format!("{} 不能作为{}发送", mime, kind.name()),
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffs_common_formats() {
+        assert_eq!(sniff_mime(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR"), Some("image/png"));
+        assert_eq!(sniff_mime(b"\xff\xd8\xff\xe0\0\x10JFIF"), Some("image/jpeg"));
+        assert_eq!(sniff_mime(b"GIF89a\x01\0\x01\0"), Some("image/gif"));
+        assert_eq!(sniff_mime(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff_mime(b"RIFF\0\0\0\0WAVEfmt "), Some("audio/wav"));
+        assert_eq!(sniff_mime(b"II*\0\x08\0\0\0"), Some("image/tiff"));
+        assert_eq!(sniff_mime(b"\x02#!SILK_V3"), Some("audio/silk"));
+        assert_eq!(sniff_mime(b"\0\0\0\x20ftypM4A \0\0\0\0"), Some("audio/mp4"));
+        assert_eq!(sniff_mime(b"\0\0\0\x1cftypavif\0\0\0\0"), Some("image/avif"));
+        assert_eq!(sniff_mime(b"\0\0\0\x18ftypheic\0\0\0\0"), Some("image/heic"));
+        assert_eq!(sniff_mime(b"\0\0\0\x18ftypisom\0\0\0\0"), Some("video/mp4"));
+        assert_eq!(sniff_mime(b"<svg xmlns="), None);
+        // 文件头不完整时不越界
+        assert_eq!(sniff_mime(b"RIFF"), None);
+        assert_eq!(sniff_mime(b""), None);
+    }
+
+    #[test]
+    fn computes_base64_len() {
+        assert_eq!(base64_len(""), 0);
+        assert_eq!(base64_len("YQ=="), 1);
+        assert_eq!(base64_len("YWI="), 2);
+        assert_eq!(base64_len("YWJj"), 3);
+        assert_eq!(base64_len(&BASE64_STANDARD.encode([0u8; 100])), 100);
+    }
+
+    #[test]
+    fn decodes_enough_base64_for_head() {
+        let data = BASE64_STANDARD.encode([7u8; 100]);
+        let head = BASE64_STANDARD.decode(base64_head(&data)).unwrap();
+        assert!(head.len() >= HEAD_LEN);
+        assert_eq!(head, [7u8; 18]);
+        // 短数据（包括带填充的）整体解码
+        let data = BASE64_STANDARD.encode(b"GIF89a!");
+        assert_eq!(BASE64_STANDARD.decode(base64_head(&data)).unwrap(), b"GIF89a!");
+        // 长度不是 4 的整数倍时截断到整数倍
+        assert_eq!(base64_head("R0lGODlhIQ"), "R0lGODlh");
+    }
+
+    #[test]
+    fn parses_local_paths() {
+        assert_eq!(local_path("file:///tmp/a.png"), Some(PathBuf::from("/tmp/a.png")));
+        assert_eq!(local_path("file:///C:/a.png"), Some(PathBuf::from("C:/a.png")));
+        assert_eq!(local_path("/tmp/a.png"), Some(PathBuf::from("/tmp/a.png")));
+        assert_eq!(local_path("a.png"), None);
+        assert_eq!(local_path("https://example.com/a.png"), None);
+    }
+
+    #[test]
+    fn rejects_only_contradicting_kinds() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR";
+        assert!(check_kind(png, MediaKind::Image).is_ok());
+        assert!(matches!(
+            check_kind(png, MediaKind::Audio),
+            Err(OneBotApiError::InvalidMedia(_))
+        ));
+        assert!(check_kind(png, MediaKind::File).is_ok());
+        assert!(check_kind(b"<svg xmlns=", MediaKind::Image).is_ok());
+        assert!(check_kind(b"\0\x01\x02\x03", MediaKind::Audio).is_ok());
+    }
+}
@@ -1,11 +1,25 @@
 use ioevent::rpc::*;
 use sithra_common::{adapt_api, api::*, model::*};
-use sithra_onebot_common::message::OneBotMessage;
+use sithra_onebot_common::{
+    api::{
+        ActionResponse, DeleteMessage, ForwardNode, GetMessage, GetMessageResponse,
+        HandleFriendRequest, HandleGroupRequest, KickMember, LeaveGroup, MuteAnonymous,
+        MuteMember, QuickOperation, QuickOperationResponse, SendForwardMessage,
+        SendForwardMessageResponse, SetAdmin, SetMemberCard, UploadFile, UploadFileResponse,
+    },
+    message::{ForwardId, OneBotMessage, OneBotSegment},
+};
 
 use crate::{
-    OneBotGenericId,
-    internal::{api::request, message::InternalSegment},
+    OneBotGenericId, build_onebot_message, build_user,
+    error::OneBotApiError,
+    internal::{
+        api::request,
+        message::{InternalForwardMessage, InternalSegment},
+    },
+    media,
     state::OneBotAdapterState,
+    webhook,
 };
 
 #[adapt_api(OneBotGenericId)]
@@ -13,17 +27,14 @@ pub async fn send_message(
     state: State<OneBotAdapterState>,
     data: SithraCall<SendMessage>,
 ) -> Result {
-    let message: SVec<InternalSegment> = data
-        .message::<OneBotMessage>()
-        .into_iter()
-        .map(|s| s.into())
-        .collect();
+    let api_client = state.api_client(&data.generic_id)?;
+    let message = build_message(data.message::<OneBotMessage>(), &state).await?;
     let echo = state.next_echo().await;
     let channel = data.take_call().channel;
     match channel.channel_type() {
         ChannelType::Private => {
             let request = request::SendPrivateMsgParams::new(channel, message);
-            let response = state.api_client.call_api(echo, request).await?;
+            let response = api_client.call_api(echo, request).await?;
             let response = SendMessageResponse {
                 message_id: Some(MessageId::new(response.message_id)),
             };
@@ -31,7 +42,7 @@ pub async fn send_message(
         }
         ChannelType::Group => {
             let request = request::SendGroupMsgParams::new(channel, message);
-            let response = state.api_client.call_api(echo, request).await?;
+            let response = api_client.call_api(echo, request).await?;
             let response = SendMessageResponse {
                 message_id: Some(MessageId::new(response.message_id)),
             };
@@ -39,3 +50,259 @@ pub async fn send_message(
         }
     }
 }
+
+/// 转换待发送的消息，并处理其中的媒体文件
+async fn build_message(
+    message: impl IntoIterator<Item = OneBotSegment>,
+    state: &OneBotAdapterState,
+) -> Result<SVec<InternalSegment>, OneBotApiError> {
+    let mut segments = SVec::<InternalSegment>::new();
+    for segment in message {
+        segments.push(media::resolve_segment(segment.into(), &state.media).await?);
+    }
+    Ok(segments)
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn send_forward_message(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<SendForwardMessage>,
+) -> Result {
+    let api_client = state.api_client(&data.generic_id)?;
+    let call = data.take_call();
+    let mut messages = Vec::with_capacity(call.nodes.len());
+    for node in call.nodes {
+        messages.push(match node {
+            ForwardNode::Reference(message_id) => {
+                InternalForwardMessage::reference(message_id.to_string())
+            }
+            ForwardNode::Custom {
+                user_id,
+                nickname,
+                content,
+            } => {
+                let content = build_message(content, &state).await?;
+                InternalForwardMessage::new(user_id, nickname, content.into_iter().collect())
+            }
+        });
+    }
+    let echo = state.next_echo().await;
+    let response = match call.channel.channel_type() {
+        ChannelType::Private => {
+            let request = request::SendPrivateForwardMsgParams::new(call.channel, messages);
+            api_client.call_api(echo, request).await?
+        }
+        ChannelType::Group => {
+            let request = request::SendGroupForwardMsgParams::new(call.channel, messages);
+            api_client.call_api(echo, request).await?
+        }
+    };
+    Ok(SendForwardMessageResponse {
+        message_id: response.message_id.map(MessageId::new),
+        forward_id: response.forward_id.map(ForwardId::new),
+    })
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn quick_operation(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<QuickOperation>,
+) -> Result {
+    let self_id = state.self_id(&data.generic_id)?;
+    let QuickOperation { context, operation } = data.take_call();
+    let handled = webhook::respond(&self_id, &context, operation);
+    Ok(QuickOperationResponse { handled })
+}
+
+fn ensure_group(channel: &Channel) -> std::result::Result<(), OneBotApiError> {
+    match channel.channel_type() {
+        ChannelType::Group => Ok(()),
+        _ => Err(OneBotApiError::NotGroupChannel),
+    }
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn kick_member(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<KickMember>,
+) -> Result {
+    let api_client = state.api_client(&data.generic_id)?;
+    let call = data.take_call();
+    ensure_group(&call.channel)?;
+    let echo = state.next_echo().await;
+    let request =
+        request::SetGroupKickParams::new(call.channel, call.user_id, call.reject_add_request);
+    api_client.call_api(echo, request).await?;
+    Ok(ActionResponse)
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn mute_member(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<MuteMember>,
+) -> Result {
+    let api_client = state.api_client(&data.generic_id)?;
+    let call = data.take_call();
+    ensure_group(&call.channel)?;
+    let echo = state.next_echo().await;
+    let duration = call.duration.min(i32::MAX as u32) as i32;
+    let request = request::SetGroupBanParams::new(call.channel, call.user_id, duration);
+    api_client.call_api(echo, request).await?;
+    Ok(ActionResponse)
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn mute_anonymous(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<MuteAnonymous>,
+) -> Result {
+    let api_client = state.api_client(&data.generic_id)?;
+    let call = data.take_call();
+    ensure_group(&call.channel)?;
+    let echo = state.next_echo().await;
+    let request = request::SetGroupAnonymousBanParams::new(call.channel, call.flag, call.duration);
+    api_client.call_api(echo, request).await?;
+    Ok(ActionResponse)
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn set_admin(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<SetAdmin>,
+) -> Result {
+    let api_client = state.api_client(&data.generic_id)?;
+    let call = data.take_call();
+    ensure_group(&call.channel)?;
+    let echo = state.next_echo().await;
+    let request = request::SetGroupAdminParams::new(call.channel, call.user_id, call.enable);
+    api_client.call_api(echo, request).await?;
+    Ok(ActionResponse)
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn set_member_card(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<SetMemberCard>,
+) -> Result {
+    let api_client = state.api_client(&data.generic_id)?;
+    let call = data.take_call();
+    ensure_group(&call.channel)?;
+    let echo = state.next_echo().await;
+    let request = request::SetGroupCardParams::new(call.channel, call.user_id, call.card);
+    api_client.call_api(echo, request).await?;
+    Ok(ActionResponse)
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn leave_group(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<LeaveGroup>,
+) -> Result {
+    let api_client = state.api_client(&data.generic_id)?;
+    let call = data.take_call();
+    ensure_group(&call.channel)?;
+    let echo = state.next_echo().await;
+    let request = request::SetGroupLeaveParams::new(call.channel, call.is_dismiss);
+    api_client.call_api(echo, request).await?;
+    Ok(ActionResponse)
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn delete_message(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<DeleteMessage>,
+) -> Result {
+    let api_client = state.api_client(&data.generic_id)?;
+    let call = data.take_call();
+    let echo = state.next_echo().await;
+    let request = request::DeleteMsgParams::new(call.message_id);
+    api_client.call_api(echo, request).await?;
+    Ok(ActionResponse)
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn get_message(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<GetMessage>,
+) -> Result {
+    let api_client = state.api_client(&data.generic_id)?;
+    let call = data.take_call();
+    let echo = state.next_echo().await;
+    let request = request::GetMsgParams::new(call.message_id);
+    let detail = api_client.call_api(echo, request).await?;
+    let channel_type = match detail.message_type.as_str() {
+        "group" => ChannelType::Group,
+        _ => ChannelType::Private,
+    };
+    let sender = build_user(
+        detail.sender.user_id as u64,
+        Some(detail.sender.nickname),
+        detail.sender.card,
+    );
+    let message = build_onebot_message(detail.message_id, detail.message.into_iter().collect());
+    Ok(GetMessageResponse {
+        time: detail.time,
+        channel_type,
+        sender,
+        message,
+    })
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn handle_friend_request(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<HandleFriendRequest>,
+) -> Result {
+    let api_client = state.api_client(&data.generic_id)?;
+    let call = data.take_call();
+    let echo = state.next_echo().await;
+    let request = request::SetFriendAddRequestParams::new(call.flag, call.approve, call.remark);
+    api_client.call_api(echo, request).await?;
+    Ok(ActionResponse)
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn handle_group_request(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<HandleGroupRequest>,
+) -> Result {
+    let api_client = state.api_client(&data.generic_id)?;
+    let call = data.take_call();
+    let echo = state.next_echo().await;
+    let request = request::SetGroupAddRequestParams::new(
+        call.flag,
+        call.sub_type,
+        call.approve,
+        call.reason,
+    );
+    api_client.call_api(echo, request).await?;
+    Ok(ActionResponse)
+}
+
+#[adapt_api(OneBotGenericId)]
+pub async fn upload_file(
+    state: State<OneBotAdapterState>,
+    data: SithraCall<UploadFile>,
+) -> Result {
+    let api_client = state.api_client(&data.generic_id)?;
+    let call = data.take_call();
+    let echo = state.next_echo().await;
+    let response = match call.channel.channel_type() {
+        ChannelType::Private => {
+            let request = request::UploadPrivateFileParams::new(call.channel, call.file, call.name);
+            api_client.call_api(echo, request).await?
+        }
+        ChannelType::Group => {
+            let request = request::UploadGroupFileParams::new(
+                call.channel,
+                call.file,
+                call.name,
+                call.folder,
+            );
+            api_client.call_api(echo, request).await?
+        }
+    };
+    Ok(UploadFileResponse {
+        file_id: response.and_then(|response| response.file_id),
+    })
+}
@@ -0,0 +1,262 @@
+use crate::{
+    api_client::{OneBotApiClient, WebSocketTransport},
+    auth,
+    config::BotConfig, error::OneBotApiError,
+    bots,
+    event_client::{self, EventClientSender, OneBotEventClient},
+};
+use log::*;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, OnceLock, RwLock};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_tungstenite::{
+    accept_hdr_async,
+    tungstenite::{
+        handshake::server::{ErrorResponse, Request, Response},
+        http::StatusCode,
+    },
+};
+
+/// 反向 WebSocket 连接的角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClientRole {
+    Api,
+    Event,
+    Universal,
+}
+
+impl ClientRole {
+    /// 根据 `X-Client-Role` 请求头判断角色，缺失时根据请求路径判断
+    fn from_request(request: &Request) -> Option<Self> {
+        let role = request
+            .headers()
+            .get("X-Client-Role")
+            .and_then(|role| role.to_str().ok());
+        match role {
+            Some(role) if role.eq_ignore_ascii_case("api") => Some(Self::Api),
+            Some(role) if role.eq_ignore_ascii_case("event") => Some(Self::Event),
+            Some(role) if role.eq_ignore_ascii_case("universal") => Some(Self::Universal),
+            _ => match request.uri().path().trim_end_matches('/') {
+                "/api" => Some(Self::Api),
+                "/event" => Some(Self::Event),
+                "" => Some(Self::Universal),
+                _ => None,
+            },
+        }
+    }
+}
+
+// 各监听地址上的反向 WebSocket 服务端，多个账号可共用同一地址
+static SERVERS: LazyLock<tokio::sync::Mutex<HashMap<String, Arc<ReverseServer>>>> =
+    LazyLock::new(Default::default);
+
+/// 一个监听地址上的反向 WebSocket 服务端，按 `X-Self-ID` 将连接分派给对应账号
+#[derive(Default)]
+struct ReverseServer {
+    routes: RwLock<Vec<Route>>,
+}
+
+/// 共用监听地址的一个账号配置
+#[derive(Clone)]
+struct Route {
+    self_id: Option<String>,
+    // 未配置 `self-id` 时，第一个分派到该配置的 `X-Self-ID`
+    assigned: Arc<OnceLock<String>>,
+    access_token: Option<String>,
+    transport: WebSocketTransport,
+    event_tx: EventClientSender,
+}
+
+impl ReverseServer {
+    /// 为接入的连接选择账号配置
+    ///
+    /// 依次匹配配置的 `self-id`、之前分派过的账号、尚未分派的未配置 `self-id` 的配置；
+    /// 该地址只有一个账号配置时总是使用它（包括缺少 `X-Self-ID` 的连接）。
+    fn route(&self, client_self_id: Option<&str>) -> Option<Route> {
+        let routes = self.routes.read().unwrap();
+        let only = match routes.as_slice() {
+            [route] => Some(route),
+            _ => None,
+        };
+        let Some(client_self_id) = client_self_id else {
+            return only.cloned();
+        };
+        routes
+            .iter()
+            .find(|route| route.self_id.as_deref() == Some(client_self_id))
+            .or_else(|| {
+                routes.iter().find(|route| {
+                    route.self_id.is_none()
+                        && route.assigned.get().map(String::as_str) == Some(client_self_id)
+                })
+            })
+            .or_else(|| {
+                routes.iter().find(|route| {
+                    route.self_id.is_none()
+                        && route.assigned.set(client_self_id.to_string()).is_ok()
+                })
+            })
+            .or(only)
+            .cloned()
+    }
+}
+
+/// 启动反向 WebSocket 服务端
+///
+/// 监听地址相同的账号共用一个服务端，接入的连接按 `X-Self-ID` 请求头分派。
+/// 返回的 API 客户端会在 OneBot 实现接入 `/api` 或 `/` 后可用，在此之前的调用会以
+/// [`OneBotApiError::Disconnected`] 失败；事件连接可通过 [`event_client::take_event_clients`] 获取。
+pub async fn start(config: &BotConfig) -> Result<OneBotApiClient, OneBotApiError> {
+    let server = {
+        let mut servers = SERVERS.lock().await;
+        match servers.get(&config.reverse_listen) {
+            Some(server) => server.clone(),
+            None => {
+                let listener = TcpListener::bind(&config.reverse_listen).await?;
+                info!("反向WebSocket正在监听: {}", config.reverse_listen);
+                let server = Arc::new(ReverseServer::default());
+                tokio::spawn(serve(listener, server.clone()));
+                servers.insert(config.reverse_listen.clone(), server.clone());
+                server
+            }
+        }
+    };
+
+    let transport = WebSocketTransport::detached();
+    server.routes.write().unwrap().push(Route {
+        self_id: config.self_id.clone(),
+        assigned: Default::default(),
+        access_token: config.access_token().map(str::to_string),
+        transport: transport.clone(),
+        event_tx: event_client::register_event_clients(bots::login(config.index)),
+    });
+
+    Ok(transport.into())
+}
+
+async fn serve(listener: TcpListener, server: Arc<ReverseServer>) {
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                error!("接受反向WebSocket连接失败: {}", e);
+                continue;
+            }
+        };
+        let server = server.clone();
+        tokio::spawn(async move {
+            if let Err(e) = accept(stream, &server).await {
+                error!("反向WebSocket握手失败 ({}): {}", addr, e);
+            }
+        });
+    }
+}
+
+#[allow(clippy::result_large_err)]
+async fn accept(stream: TcpStream, server: &ReverseServer) -> Result<(), OneBotApiError> {
+    let mut role = None;
+    let mut client_self_id = None;
+    let mut route = None;
+    let callback = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        let reject = |status, reason: &str| {
+            let mut response = ErrorResponse::new(Some(reason.to_string()));
+            *response.status_mut() = status;
+            response
+        };
+        client_self_id = request
+            .headers()
+            .get("X-Self-ID")
+            .and_then(|id| id.to_str().ok())
+            .map(str::to_string);
+        let Some(matched) = server.route(client_self_id.as_deref()) else {
+            return Err(reject(StatusCode::FORBIDDEN, "unknown self id"));
+        };
+        if let Err(status) = auth::verify_request(request, matched.access_token.as_deref()) {
+            return Err(reject(status, "unauthorized"));
+        }
+        role = ClientRole::from_request(request);
+        if role.is_none() {
+            return Err(reject(StatusCode::NOT_FOUND, "unknown endpoint"));
+        }
+        route = Some(matched);
+        Ok(response)
+    };
+    let ws_stream = accept_hdr_async(stream, callback).await?;
+    let (Some(role), Some(route)) = (role, route) else {
+        return Err(OneBotApiError::InvalidMessage);
+    };
+
+    match (&client_self_id, &route.self_id) {
+        (Some(id), Some(self_id)) if id != self_id => {
+            warn!("反向WebSocket连接的 X-Self-ID ({}) 与配置不符", id)
+        }
+        (Some(_), _) => {}
+        (None, _) => warn!("反向WebSocket连接缺少 X-Self-ID 请求头"),
+    }
+    info!(
+        "反向WebSocket已接入: {:?} ({})",
+        role,
+        client_self_id.as_deref().unwrap_or("未知账号")
+    );
+
+    match role {
+        ClientRole::Api => {
+            route.transport.attach(ws_stream, None);
+        }
+        ClientRole::Event => {
+            let _ = route.event_tx.send(OneBotEventClient::from_stream(ws_stream));
+        }
+        ClientRole::Universal => {
+            let (tx, rx) = mpsc::unbounded_channel();
+            route.transport.attach(ws_stream, Some(tx));
+            let _ = route.event_tx.send(OneBotEventClient::from_channel(rx));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(self_id: Option<&str>) -> Route {
+        Route {
+            self_id: self_id.map(str::to_string),
+            assigned: Default::default(),
+            access_token: None,
+            transport: WebSocketTransport::detached(),
+            event_tx: event_client::register_event_clients(bots::Login::default()),
+        }
+    }
+
+    fn server(routes: impl IntoIterator<Item = Route>) -> ReverseServer {
+        let server = ReverseServer::default();
+        server.routes.write().unwrap().extend(routes);
+        server
+    }
+
+    #[test]
+    fn routes_by_self_id() {
+        let server = server([route(Some("10001")), route(None), route(None)]);
+        let routed = |id| server.route(id).map(|route| route.transport);
+        let configured = server.route(Some("10001")).unwrap();
+        assert_eq!(configured.self_id.as_deref(), Some("10001"));
+        // 未配置 `self-id` 的配置依次分派给不同账号，之后同一账号总是分派到同一配置
+        let first = server.route(Some("10002")).unwrap();
+        let second = server.route(Some("10003")).unwrap();
+        assert_eq!(first.assigned.get().map(String::as_str), Some("10002"));
+        assert_eq!(second.assigned.get().map(String::as_str), Some("10003"));
+        let again = server.route(Some("10002")).unwrap();
+        assert_eq!(again.assigned.get().map(String::as_str), Some("10002"));
+        assert!(routed(Some("10004")).is_none());
+        assert!(routed(None).is_none());
+    }
+
+    #[test]
+    fn single_route_accepts_any_client() {
+        let server = server([route(Some("20001"))]);
+        assert!(server.route(None).is_some());
+        assert!(server.route(Some("20002")).is_some());
+    }
+}
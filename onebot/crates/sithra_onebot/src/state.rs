@@ -1,13 +1,25 @@
 #![allow(unused)]
 use ioevent::rpc::*;
+use log::*;
+use sithra_common::prelude::*;
+use std::time::Duration;
 
-use crate::{OneBotGenericId, api_client::OneBotApiClient, config::OneBotConfig};
+use crate::{
+    OneBotGenericId,
+    api_client::{OneBotApiClient, WebSocketTransport},
+    bots,
+    config::{ApiTransportKind, BotConfig, ConnectionMode, OneBotConfig},
+    error::OneBotApiError,
+    event_client,
+    internal::api::request::GetLoginInfoParams,
+    media::MediaConfig,
+    reverse_server,
+};
 
 #[derive(Clone)]
 pub struct OneBotAdapterState {
-    pub api_client: OneBotApiClient,
     pub pdw: DefaultProcedureWright,
-    pub base_generic_id: OneBotGenericId,
+    pub media: MediaConfig,
 }
 impl ProcedureCallWright for OneBotAdapterState {
     fn next_echo(&self) -> impl Future<Output = u64> + Send + Sync {
@@ -17,12 +29,109 @@ impl ProcedureCallWright for OneBotAdapterState {
 impl OneBotAdapterState {
     pub async fn new() -> Self {
         let config = OneBotConfig::load().unwrap();
-        let generic_id = OneBotGenericId::from_config(&config);
-        let ws_api = crate::join_url(&config.ws_url, "/api");
+        let pdw = DefaultProcedureWright::default();
+        for bot in config.bots() {
+            let api_client = connect(&bot).await;
+            let account = bots::login(bot.index);
+            if bot.mode == ConnectionMode::Webhook {
+                // API 可能不可达，允许以配置的账号或第一个事件中的账号登记
+                account.allow_claim(api_client.clone());
+                if let Some(self_id) = &bot.self_id {
+                    account.claim(self_id);
+                }
+            }
+            // 连接可能尚未建立，在后台获取登录信息
+            tokio::spawn(login(api_client, pdw.clone(), bot.self_id.clone(), account));
+        }
         Self {
-            api_client: OneBotApiClient::new(&ws_api).await.unwrap(),
-            pdw: DefaultProcedureWright::default(),
-            base_generic_id: generic_id,
+            pdw,
+            media: MediaConfig::from_config(&config),
+        }
+    }
+
+    /// 根据调用方的 GenericId 取得对应账号的 API 客户端
+    pub fn api_client(&self, generic_id: &GenericId) -> Result<OneBotApiClient, OneBotApiError> {
+        let self_id = self.self_id(generic_id)?;
+        bots::get(&self_id).ok_or(OneBotApiError::BotNotConnected(self_id))
+    }
+
+    /// 调用方所指定的机器人账号
+    pub fn self_id(&self, generic_id: &GenericId) -> Result<String, OneBotApiError> {
+        let generic_id =
+            OneBotGenericId::ensure_generic_id(generic_id).map_err(OneBotApiError::Internal)?;
+        Ok(generic_id.self_id)
+    }
+}
+
+/// 按配置建立账号的 API 客户端
+async fn connect(config: &BotConfig) -> OneBotApiClient {
+    // 反向 WebSocket 与通用连接同时承载事件，无论 API 走哪种传输都需要建立
+    let ws_client = match config.mode {
+        ConnectionMode::Reverse => Some(match reverse_server::start(config).await {
+            Ok(client) => client,
+            Err(e) => {
+                error!("反向WebSocket监听 {} 失败: {}", config.reverse_listen, e);
+                // 不会有连接接入，该账号的 API 调用均以连接断开失败
+                WebSocketTransport::detached().into()
+            }
+        }),
+        ConnectionMode::Forward if config.ws_universal => Some(OneBotApiClient::universal(
+            &config.ws_url,
+            config.access_token(),
+            event_client::register_event_clients(bots::login(config.index)),
+        )),
+        ConnectionMode::Forward | ConnectionMode::Webhook => None,
+    };
+    match config.api_transport {
+        ApiTransportKind::WebSocket => match ws_client {
+            Some(client) => client,
+            None => {
+                let ws_api = crate::join_url(&config.ws_url, "/api");
+                OneBotApiClient::new(&ws_api, config.access_token())
+            }
+        },
+        ApiTransportKind::Http => {
+            OneBotApiClient::http(&config.http_url, config.access_token()).unwrap()
         }
     }
+    .with_retries(config.api_retries)
+}
+
+/// 通过 `get_login_info` 获取当前登录的账号，并登记为已连接的机器人
+///
+/// 配置了 `self-id`（或已由事件登记了账号）且与实际登录账号不符时拒绝登记。
+async fn login(
+    api_client: OneBotApiClient,
+    pdw: DefaultProcedureWright,
+    configured_self_id: Option<String>,
+    login: bots::Login,
+) {
+    let mut backoff = Duration::from_secs(1);
+    let login_info = loop {
+        let echo = pdw.next_echo().await;
+        match api_client.call_api(echo, GetLoginInfoParams::new()).await {
+            Ok(login_info) => break login_info,
+            Err(e) => {
+                warn!("获取登录信息失败: {}，{} 秒后重试", e, backoff.as_secs());
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(Duration::from_secs(30));
+            }
+        }
+    };
+
+    let self_id = login_info.user_id.to_string();
+    login.set(self_id.clone());
+    // 账号可能已由事件登记，见 [`bots::Login::claim`]
+    let expected_self_id = configured_self_id.or_else(|| login.self_id().map(str::to_string));
+    if let Some(expected_self_id) = expected_self_id.filter(|expected| *expected != self_id) {
+        error!(
+            "配置的 self-id ({}) 与当前登录账号 ({}) 不符，已拒绝使用该账号",
+            expected_self_id, self_id
+        );
+        bots::unregister(&expected_self_id);
+        bots::reject(self_id);
+        return;
+    }
+    info!("当前登录账号: {} ({})", login_info.nickname, self_id);
+    bots::register(self_id, api_client);
 }
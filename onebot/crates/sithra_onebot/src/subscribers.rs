@@ -3,4 +3,19 @@ use ioevent::prelude::*;
 use crate::procedure::*;
 use crate::state::OneBotAdapterState;
 
-pub const SUBSCRIBERS: &[Subscriber<OneBotAdapterState>] = &[create_subscriber!(send_message)];
+pub const SUBSCRIBERS: &[Subscriber<OneBotAdapterState>] = &[
+    create_subscriber!(send_message),
+    create_subscriber!(send_forward_message),
+    create_subscriber!(quick_operation),
+    create_subscriber!(kick_member),
+    create_subscriber!(mute_member),
+    create_subscriber!(mute_anonymous),
+    create_subscriber!(set_admin),
+    create_subscriber!(set_member_card),
+    create_subscriber!(leave_group),
+    create_subscriber!(delete_message),
+    create_subscriber!(get_message),
+    create_subscriber!(handle_friend_request),
+    create_subscriber!(handle_group_request),
+    create_subscriber!(upload_file),
+];
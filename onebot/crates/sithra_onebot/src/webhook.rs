@@ -0,0 +1,192 @@
+use crate::{
+    config::BotConfig,
+    error::OneBotApiError,
+    internal::event::{
+        InternalMessageEvent, InternalOnebotEvent, InternalOnebotEventKind, InternalRequestEvent,
+    },
+};
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+};
+use dashmap::DashMap;
+use hmac::{Hmac, Mac};
+use log::*;
+use serde_json::Value;
+use sha1::Sha1;
+use std::sync::{Arc, LazyLock};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Duration;
+
+// 等待快速操作的事件，键为 (机器人账号, 消息 ID 或请求的 flag)
+static QUICK_OPERATIONS: LazyLock<DashMap<(String, String), oneshot::Sender<Value>>> =
+    LazyLock::new(DashMap::new);
+
+struct WebhookState {
+    secret: Option<String>,
+    quick_operation_timeout: Duration,
+    events: mpsc::UnboundedSender<String>,
+}
+
+/// 启动 HTTP POST 事件上报服务端
+///
+/// 通过校验的事件原文会被发送至 `events`。
+pub async fn serve(
+    config: &BotConfig,
+    events: mpsc::UnboundedSender<String>,
+) -> Result<(), OneBotApiError> {
+    let state = Arc::new(WebhookState {
+        secret: config.webhook_secret.clone().filter(|s| !s.is_empty()),
+        quick_operation_timeout: Duration::from_millis(config.quick_operation_timeout),
+        events,
+    });
+    let app = Router::new()
+        .route("/", post(handle_event))
+        .with_state(state);
+
+    let listener = TcpListener::bind(&config.webhook_listen).await?;
+    info!("HTTP事件上报正在监听: {}", config.webhook_listen);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// 对尚在等待的事件做出快速操作
+///
+/// # 参数
+/// - `self_id`: 收到该事件的机器人账号
+/// - `context`: 消息 ID 或请求的 flag
+/// - `operation`: 快速操作内容
+///
+/// 返回是否有对应的事件在等待。
+pub fn respond(self_id: &str, context: &str, operation: Value) -> bool {
+    match QUICK_OPERATIONS.remove(&(self_id.to_string(), context.to_string())) {
+        Some((_, sender)) => sender.send(operation).is_ok(),
+        None => false,
+    }
+}
+
+async fn handle_event(
+    State(state): State<Arc<WebhookState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Some(secret) = &state.secret {
+        let Some(signature) = headers
+            .get("X-Signature")
+            .and_then(|s| s.to_str().ok())
+        else {
+            return StatusCode::UNAUTHORIZED.into_response();
+        };
+        if !verify_signature(secret, &body, signature) {
+            warn!("HTTP事件上报签名校验失败");
+            return StatusCode::FORBIDDEN.into_response();
+        }
+    }
+
+    let Ok(text) = String::from_utf8(body.to_vec()) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let event: InternalOnebotEvent = match serde_json::from_str(&text) {
+        Ok(event) => event,
+        Err(e) => {
+            error!("解析HTTP上报事件失败: {}", e);
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
+    // 先登记再分发，避免插件响应早于登记
+    let context = quick_operation_context(&event.kind)
+        .filter(|_| !state.quick_operation_timeout.is_zero())
+        .map(|context| (event.self_id.to_string(), context));
+    let operation = context.as_ref().map(|context| {
+        let (tx, rx) = oneshot::channel();
+        QUICK_OPERATIONS.insert(context.clone(), tx);
+        rx
+    });
+
+    if state.events.send(text).is_err() {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    }
+
+    let (Some(context), Some(operation)) = (context, operation) else {
+        return StatusCode::NO_CONTENT.into_response();
+    };
+    match tokio::time::timeout(state.quick_operation_timeout, operation).await {
+        Ok(Ok(operation)) => Json(operation).into_response(),
+        _ => {
+            QUICK_OPERATIONS.remove(&context);
+            StatusCode::NO_CONTENT.into_response()
+        }
+    }
+}
+
+/// 校验 `X-Signature: sha1=<HMAC-SHA1 十六进制>` 请求头
+fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(signature) = signature.strip_prefix("sha1=") else {
+        return false;
+    };
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha1>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// 支持快速操作的事件的上下文标识
+fn quick_operation_context(kind: &InternalOnebotEventKind) -> Option<String> {
+    match kind {
+        InternalOnebotEventKind::Message(InternalMessageEvent::Private(msg)) => {
+            Some(msg.message_id.to_string())
+        }
+        InternalOnebotEventKind::Message(InternalMessageEvent::Group(msg)) => {
+            Some(msg.message_id.to_string())
+        }
+        InternalOnebotEventKind::Request(InternalRequestEvent::Friend(req)) => {
+            Some(req.flag.clone())
+        }
+        InternalOnebotEventKind::Request(InternalRequestEvent::Group(req)) => {
+            Some(req.flag.clone())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 2202 中 HMAC-SHA1 的第 2 组测试数据
+    const SECRET: &str = "Jefe";
+    const BODY: &[u8] = b"what do ya want for nothing?";
+    const SIGNATURE: &str = "sha1=effcdf6ae5eb2fa2d27416d5f184df9c259a7c79";
+
+    #[test]
+    fn accepts_valid_signature() {
+        assert!(verify_signature(SECRET, BODY, SIGNATURE));
+        // 十六进制不区分大小写
+        assert!(verify_signature(SECRET, BODY, "sha1=EFFCDF6AE5EB2FA2D27416D5F184DF9C259A7C79"));
+    }
+
+    #[test]
+    fn rejects_wrong_secret_or_body() {
+        assert!(!verify_signature("jefe", BODY, SIGNATURE));
+        assert!(!verify_signature(SECRET, b"what do ya want for nothing!", SIGNATURE));
+    }
+
+    #[test]
+    fn rejects_malformed_signature() {
+        assert!(!verify_signature(SECRET, BODY, "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79"));
+        assert!(!verify_signature(SECRET, BODY, "sha256=effcdf6ae5eb2fa2d27416d5f184df9c259a7c79"));
+        assert!(!verify_signature(SECRET, BODY, "sha1=not-hex"));
+        assert!(!verify_signature(SECRET, BODY, "sha1=effcdf"));
+        assert!(!verify_signature(SECRET, BODY, ""));
+    }
+}
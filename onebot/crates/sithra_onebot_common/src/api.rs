@@ -0,0 +1,401 @@
+use ioevent::rpc::*;
+use serde::{Deserialize, Serialize};
+use sithra_common::model::{Channel, ChannelType, MessageId, User, UserId};
+
+use crate::message::{ForwardId, OneBotMessage};
+
+/// 无返回数据的操作响应
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct ActionResponse;
+
+/// 快速操作
+///
+/// 仅在 HTTP POST 事件上报模式下、且配置了 `quick-operation-timeout` 时有效，
+/// 需在事件上报的等待时间内调用。
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct QuickOperation {
+    /// 事件上下文（消息 ID 或请求的 flag）
+    pub context: String,
+    /// 快速操作内容，如 `{"reply": "..."}`、`{"approve": true}`
+    pub operation: serde_json::Value,
+}
+impl ProcedureCallRequest for QuickOperation {
+    type RESPONSE = QuickOperationResponse;
+}
+impl QuickOperation {
+    pub fn new<S: ToString>(context: S, operation: serde_json::Value) -> Self {
+        Self {
+            context: context.to_string(),
+            operation,
+        }
+    }
+}
+
+/// 快速操作响应
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct QuickOperationResponse {
+    /// 是否有对应的事件在等待快速操作
+    pub handled: bool,
+}
+
+/// 群组踢人
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct KickMember {
+    /// 目标群
+    pub channel: Channel,
+    /// 被踢用户
+    pub user_id: UserId,
+    /// 是否拒绝此人的后续加群请求
+    pub reject_add_request: bool,
+}
+impl ProcedureCallRequest for KickMember {
+    type RESPONSE = ActionResponse;
+}
+impl KickMember {
+    pub fn new<U: Into<UserId>>(channel: Channel, user_id: U, reject_add_request: bool) -> Self {
+        Self {
+            channel,
+            user_id: user_id.into(),
+            reject_add_request,
+        }
+    }
+}
+
+/// 群组禁言
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct MuteMember {
+    /// 目标群
+    pub channel: Channel,
+    /// 被禁言用户
+    pub user_id: UserId,
+    /// 禁言时长（秒），0 表示解除禁言
+    pub duration: u32,
+}
+impl ProcedureCallRequest for MuteMember {
+    type RESPONSE = ActionResponse;
+}
+impl MuteMember {
+    pub fn new<U: Into<UserId>>(channel: Channel, user_id: U, duration: u32) -> Self {
+        Self {
+            channel,
+            user_id: user_id.into(),
+            duration,
+        }
+    }
+}
+
+/// 群组匿名用户禁言
+///
+/// 匿名用户无法解除禁言。
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct MuteAnonymous {
+    /// 目标群
+    pub channel: Channel,
+    /// 匿名用户 flag（来自 [`GroupAnonymousMessageEvent`](crate::event::GroupAnonymousMessageEvent)）
+    pub flag: String,
+    /// 禁言时长（秒）
+    pub duration: u32,
+}
+impl ProcedureCallRequest for MuteAnonymous {
+    type RESPONSE = ActionResponse;
+}
+impl MuteAnonymous {
+    pub fn new<S: ToString>(channel: Channel, flag: S, duration: u32) -> Self {
+        Self {
+            channel,
+            flag: flag.to_string(),
+            duration,
+        }
+    }
+}
+
+/// 设置或取消群管理员
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct SetAdmin {
+    /// 目标群
+    pub channel: Channel,
+    /// 目标用户
+    pub user_id: UserId,
+    /// `true` 为设置，`false` 为取消
+    pub enable: bool,
+}
+impl ProcedureCallRequest for SetAdmin {
+    type RESPONSE = ActionResponse;
+}
+impl SetAdmin {
+    pub fn new<U: Into<UserId>>(channel: Channel, user_id: U, enable: bool) -> Self {
+        Self {
+            channel,
+            user_id: user_id.into(),
+            enable,
+        }
+    }
+}
+
+/// 设置群名片
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct SetMemberCard {
+    /// 目标群
+    pub channel: Channel,
+    /// 目标用户
+    pub user_id: UserId,
+    /// 新群名片，为空表示删除群名片
+    pub card: String,
+}
+impl ProcedureCallRequest for SetMemberCard {
+    type RESPONSE = ActionResponse;
+}
+impl SetMemberCard {
+    pub fn new<U: Into<UserId>, S: ToString>(channel: Channel, user_id: U, card: S) -> Self {
+        Self {
+            channel,
+            user_id: user_id.into(),
+            card: card.to_string(),
+        }
+    }
+}
+
+/// 退出群组
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct LeaveGroup {
+    /// 目标群
+    pub channel: Channel,
+    /// 是否解散（仅群主可用）
+    pub is_dismiss: bool,
+}
+impl ProcedureCallRequest for LeaveGroup {
+    type RESPONSE = ActionResponse;
+}
+impl LeaveGroup {
+    pub fn new(channel: Channel, is_dismiss: bool) -> Self {
+        Self {
+            channel,
+            is_dismiss,
+        }
+    }
+}
+
+/// 撤回消息
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct DeleteMessage {
+    /// 要撤回的消息 ID
+    pub message_id: MessageId,
+}
+impl ProcedureCallRequest for DeleteMessage {
+    type RESPONSE = ActionResponse;
+}
+impl DeleteMessage {
+    pub fn new<M: Into<MessageId>>(message_id: M) -> Self {
+        Self {
+            message_id: message_id.into(),
+        }
+    }
+}
+
+/// 获取消息
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct GetMessage {
+    /// 目标消息 ID
+    pub message_id: MessageId,
+}
+impl ProcedureCallRequest for GetMessage {
+    type RESPONSE = GetMessageResponse;
+}
+impl GetMessage {
+    pub fn new<M: Into<MessageId>>(message_id: M) -> Self {
+        Self {
+            message_id: message_id.into(),
+        }
+    }
+}
+
+/// 获取消息响应
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct GetMessageResponse {
+    /// 消息发送时间戳
+    pub time: i64,
+    /// 消息所在的频道类型
+    pub channel_type: ChannelType,
+    /// 发送者
+    pub sender: User,
+    /// 消息内容
+    pub message: OneBotMessage,
+}
+
+/// 处理加好友请求
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct HandleFriendRequest {
+    /// 请求 flag（来自 `FriendRequestEvent`）
+    pub flag: String,
+    /// 是否同意
+    pub approve: bool,
+    /// 好友备注（仅同意时有效）
+    pub remark: String,
+}
+impl ProcedureCallRequest for HandleFriendRequest {
+    type RESPONSE = ActionResponse;
+}
+impl HandleFriendRequest {
+    /// 同意请求并设置备注
+    pub fn approve<S: ToString, R: ToString>(flag: S, remark: R) -> Self {
+        Self {
+            flag: flag.to_string(),
+            approve: true,
+            remark: remark.to_string(),
+        }
+    }
+    /// 拒绝请求
+    pub fn reject<S: ToString>(flag: S) -> Self {
+        Self {
+            flag: flag.to_string(),
+            approve: false,
+            remark: String::new(),
+        }
+    }
+}
+
+/// 处理加群请求或邀请
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct HandleGroupRequest {
+    /// 请求 flag（来自 `GroupRequestEvent`）
+    pub flag: String,
+    /// 请求类型（来自 `GroupRequestEvent`）
+    pub sub_type: String,
+    /// 是否同意
+    pub approve: bool,
+    /// 拒绝理由（仅拒绝时有效）
+    pub reason: String,
+}
+impl ProcedureCallRequest for HandleGroupRequest {
+    type RESPONSE = ActionResponse;
+}
+impl HandleGroupRequest {
+    /// 同意请求
+    pub fn approve<S: ToString, T: ToString>(flag: S, sub_type: T) -> Self {
+        Self {
+            flag: flag.to_string(),
+            sub_type: sub_type.to_string(),
+            approve: true,
+            reason: String::new(),
+        }
+    }
+    /// 拒绝请求并附带理由
+    pub fn reject<S: ToString, T: ToString, R: ToString>(flag: S, sub_type: T, reason: R) -> Self {
+        Self {
+            flag: flag.to_string(),
+            sub_type: sub_type.to_string(),
+            approve: false,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// 上传文件
+///
+/// 根据频道类型上传为群文件或私聊文件。
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct UploadFile {
+    /// 目标频道
+    pub channel: Channel,
+    /// 文件路径（OneBot 实现所在机器上的本地路径）
+    pub file: String,
+    /// 文件名
+    pub name: String,
+    /// 群文件的父目录 ID（为空时上传到根目录，私聊时忽略）
+    pub folder: Option<String>,
+}
+impl ProcedureCallRequest for UploadFile {
+    type RESPONSE = UploadFileResponse;
+}
+impl UploadFile {
+    pub fn new<F: ToString, N: ToString>(channel: Channel, file: F, name: N) -> Self {
+        Self {
+            channel,
+            file: file.to_string(),
+            name: name.to_string(),
+            folder: None,
+        }
+    }
+    /// 上传到指定的群文件目录
+    pub fn with_folder<S: ToString>(mut self, folder: S) -> Self {
+        self.folder = Some(folder.to_string());
+        self
+    }
+}
+
+/// 上传文件响应
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct UploadFileResponse {
+    /// 文件 ID（部分 OneBot 实现不返回）
+    pub file_id: Option<String>,
+}
+
+/// 合并转发中的一个节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ForwardNode {
+    /// 引用已有的消息
+    Reference(MessageId),
+    /// 自定义发送者与内容的消息
+    Custom {
+        /// 显示的发送者
+        user_id: UserId,
+        /// 显示的发送者昵称
+        nickname: String,
+        /// 消息内容
+        content: OneBotMessage,
+    },
+}
+
+/// 发送合并转发消息
+///
+/// ```ignore
+/// let request = SendForwardMessage::new(channel)
+///     .node("10001", "Alice", message)
+///     .reference(message_id);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct SendForwardMessage {
+    /// 目标频道
+    pub channel: Channel,
+    /// 转发的节点
+    pub nodes: Vec<ForwardNode>,
+}
+impl ProcedureCallRequest for SendForwardMessage {
+    type RESPONSE = SendForwardMessageResponse;
+}
+impl SendForwardMessage {
+    pub fn new(channel: Channel) -> Self {
+        Self {
+            channel,
+            nodes: Vec::new(),
+        }
+    }
+    /// 添加自定义消息节点
+    pub fn node<U: Into<UserId>, N: ToString>(
+        mut self,
+        user_id: U,
+        nickname: N,
+        content: OneBotMessage,
+    ) -> Self {
+        self.nodes.push(ForwardNode::Custom {
+            user_id: user_id.into(),
+            nickname: nickname.to_string(),
+            content,
+        });
+        self
+    }
+    /// 添加引用已有消息的节点
+    pub fn reference<M: Into<MessageId>>(mut self, message_id: M) -> Self {
+        self.nodes.push(ForwardNode::Reference(message_id.into()));
+        self
+    }
+}
+
+/// 发送合并转发消息响应
+#[derive(Debug, Clone, Serialize, Deserialize, ProcedureCall)]
+pub struct SendForwardMessageResponse {
+    /// 消息 ID
+    pub message_id: Option<MessageId>,
+    /// 转发 ID，可用于 `OneBotSegment::forward`（部分 OneBot 实现不返回）
+    pub forward_id: Option<ForwardId>,
+}
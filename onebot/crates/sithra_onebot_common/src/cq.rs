@@ -0,0 +1,424 @@
+//! CQ 码的解析与序列化
+//!
+//! 部分 OneBot 实现以字符串格式（`message_format: string`）上报消息，
+//! `raw_message` 也总是 CQ 码，例如 `你好[CQ:at,qq=123][CQ:face,id=1]`。
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use serde_json::Value;
+use sithra_common::message::Message;
+
+use crate::message::{
+    ContactKind, FileInfo, ForwardId, ImageInfo, Music, OneBotMessage, OneBotSegment, param_to_string,
+};
+
+/// CQ 码中的一个消息段
+///
+/// 纯文本以 `text` 类型、`text` 参数表示，与数组格式的消息段一致。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CqSegment {
+    /// 消息段类型
+    pub r#type: String,
+    /// 参数，保持原有顺序
+    pub data: Vec<(String, String)>,
+}
+
+impl CqSegment {
+    pub fn new<S: ToString>(r#type: S, data: Vec<(String, String)>) -> Self {
+        Self {
+            r#type: r#type.to_string(),
+            data,
+        }
+    }
+    /// 生成纯文本消息段
+    pub fn text<S: ToString>(text: S) -> Self {
+        Self::new("text", vec![("text".to_string(), text.to_string())])
+    }
+    /// 获取参数
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.data
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+    /// 是否为纯文本消息段
+    pub fn is_text(&self) -> bool {
+        self.r#type == "text"
+    }
+}
+
+impl fmt::Display for CqSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_text() {
+            return f.write_str(&escape(self.get("text").unwrap_or_default(), false));
+        }
+        write!(f, "[CQ:{}", self.r#type)?;
+        for (key, value) in &self.data {
+            write!(f, ",{}={}", key, escape(value, true))?;
+        }
+        f.write_str("]")
+    }
+}
+
+/// CQ 码字符串
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CqCode(pub Vec<CqSegment>);
+
+impl CqCode {
+    /// 解析 CQ 码字符串
+    ///
+    /// 不完整的 CQ 码（如缺少 `]`）按纯文本处理。
+    pub fn parse(s: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut rest = s;
+        while !rest.is_empty() {
+            let code = rest
+                .find("[CQ:")
+                .and_then(|start| Some((start, start + rest[start..].find(']')?)));
+            let Some((start, end)) = code else {
+                segments.push(CqSegment::text(unescape(rest)));
+                break;
+            };
+            if start > 0 {
+                segments.push(CqSegment::text(unescape(&rest[..start])));
+            }
+            segments.push(parse_code(&rest[start + 4..end]));
+            rest = &rest[end + 1..];
+        }
+        Self(segments)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CqSegment> {
+        self.0.iter()
+    }
+}
+
+/// 解析 `[CQ:` 与 `]` 之间的内容
+fn parse_code(code: &str) -> CqSegment {
+    let mut parts = code.split(',');
+    let r#type = parts.next().unwrap_or_default().trim();
+    let data = parts
+        .filter_map(|part| {
+            let (key, value) = part.split_once('=')?;
+            Some((key.trim().to_string(), unescape(value)))
+        })
+        .collect();
+    CqSegment::new(r#type, data)
+}
+
+impl FromStr for CqCode {
+    type Err = Infallible;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::parse(s))
+    }
+}
+
+impl fmt::Display for CqCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|segment| write!(f, "{}", segment))
+    }
+}
+
+impl FromIterator<CqSegment> for CqCode {
+    fn from_iter<T: IntoIterator<Item = CqSegment>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for CqCode {
+    type Item = CqSegment;
+    type IntoIter = std::vec::IntoIter<CqSegment>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// 转义 CQ 码中的特殊字符
+///
+/// 纯文本中转义 `&`、`[`、`]`；参数值中还需转义 `,`。
+pub fn escape(text: &str, in_param: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '[' => escaped.push_str("&#91;"),
+            ']' => escaped.push_str("&#93;"),
+            ',' if in_param => escaped.push_str("&#44;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 还原 CQ 码中被转义的字符
+pub fn unescape(text: &str) -> String {
+    text.replace("&#44;", ",")
+        .replace("&#91;", "[")
+        .replace("&#93;", "]")
+        .replace("&amp;", "&")
+}
+
+impl From<OneBotSegment> for CqSegment {
+    fn from(value: OneBotSegment) -> Self {
+        let param = |k: &str, v: String| (k.to_string(), v);
+        match value {
+            OneBotSegment::Text(text) => CqSegment::text(text),
+            OneBotSegment::Image(info) => image_to_cq(info),
+            OneBotSegment::Face(id) => CqSegment::new("face", vec![param("id", id)]),
+            OneBotSegment::At(user_id) => {
+                CqSegment::new("at", vec![param("qq", user_id.to_string())])
+            }
+            OneBotSegment::AtAll => CqSegment::new("at", vec![param("qq", "all".to_string())]),
+            OneBotSegment::Record(url) => CqSegment::new("record", vec![param("file", url)]),
+            OneBotSegment::Video(info) => file_to_cq("video", info),
+            OneBotSegment::File(info) => file_to_cq("file", info),
+            OneBotSegment::Poke(user_id) => CqSegment::new(
+                "poke",
+                vec![param("type", "poke".to_string()), param("id", user_id.to_string())],
+            ),
+            OneBotSegment::Location { lat, lon } => CqSegment::new(
+                "location",
+                vec![param("lat", lat.to_string()), param("lon", lon.to_string())],
+            ),
+            OneBotSegment::Reply(message_id) => {
+                CqSegment::new("reply", vec![param("id", message_id.to_string())])
+            }
+            OneBotSegment::Forward(forward_id) => {
+                CqSegment::new("forward", vec![param("id", forward_id.to_string())])
+            }
+            OneBotSegment::Share {
+                url,
+                title,
+                content,
+                image,
+            } => {
+                let mut data = vec![param("url", url), param("title", title)];
+                data.extend(content.map(|content| param("content", content)));
+                data.extend(image.map(|image| param("image", image)));
+                CqSegment::new("share", data)
+            }
+            OneBotSegment::Contact { kind, id } => CqSegment::new(
+                "contact",
+                vec![param("type", kind.as_str().to_string()), param("id", id)],
+            ),
+            OneBotSegment::Json(data) => CqSegment::new("json", vec![param("data", data)]),
+            OneBotSegment::Xml(data) => CqSegment::new("xml", vec![param("data", data)]),
+            OneBotSegment::Music(music) => CqSegment::new("music", music.into_params()),
+            OneBotSegment::Dice(result) => CqSegment::new(
+                "dice",
+                result.map(|result| param("result", result.to_string())).into_iter().collect(),
+            ),
+            OneBotSegment::Rps(result) => CqSegment::new(
+                "rps",
+                result.map(|result| param("result", result.to_string())).into_iter().collect(),
+            ),
+            OneBotSegment::Shake => CqSegment::new("shake", Vec::new()),
+            OneBotSegment::Unknown { r#type, data } => CqSegment::new(
+                r#type,
+                data.into_iter()
+                    .filter_map(|(key, value)| Some((key, param_to_string(value)?)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<CqSegment> for OneBotSegment {
+    fn from(value: CqSegment) -> Self {
+        let get = |key: &str| value.get(key).map(str::to_string);
+        let segment = match value.r#type.as_str() {
+            "text" => get("text").map(OneBotSegment::text),
+            "image" => parse_image(&value).map(OneBotSegment::Image),
+            "face" => get("id").map(OneBotSegment::face),
+            "video" => parse_file(&value).map(OneBotSegment::Video),
+            "file" => parse_file(&value).map(OneBotSegment::File),
+            "record" => get("url").or_else(|| get("file")).map(OneBotSegment::record),
+            "at" => get("qq").or_else(|| get("id")).map(|id| match id.as_str() {
+                "all" => OneBotSegment::AtAll,
+                id => OneBotSegment::at(id),
+            }),
+            "poke" => get("id").map(|id| OneBotSegment::poke(id.as_str())),
+            "location" => parse_location(&value),
+            "reply" => get("id").map(|id| OneBotSegment::reply(id.as_str())),
+            "forward" => get("id").map(|id| OneBotSegment::forward(ForwardId::new(id))),
+            "share" => parse_share(&value),
+            "contact" => value.get("type").and_then(ContactKind::parse).and_then(|kind| {
+                let id = get("id")?;
+                Some(OneBotSegment::Contact { kind, id })
+            }),
+            "json" => get("data").map(OneBotSegment::json),
+            "xml" => get("data").map(OneBotSegment::xml),
+            "music" => Music::from_params(get).map(OneBotSegment::Music),
+            "dice" => Some(OneBotSegment::Dice(get("result").and_then(|r| r.parse().ok()))),
+            "rps" => Some(OneBotSegment::Rps(get("result").and_then(|r| r.parse().ok()))),
+            "shake" => Some(OneBotSegment::Shake),
+            _ => None,
+        };
+        segment.unwrap_or_else(|| {
+            let data = value
+                .data
+                .into_iter()
+                .map(|(key, value)| (key, Value::String(value)))
+                .collect();
+            OneBotSegment::unknown(value.r#type, data)
+        })
+    }
+}
+
+fn image_to_cq(info: ImageInfo) -> CqSegment {
+    let param = |k: &str, v: String| (k.to_string(), v);
+    let mut data = vec![param("file", info.file)];
+    if info.flash {
+        data.push(param("type", "flash".to_string()));
+    }
+    data.extend(info.sub_type.map(|value| param("sub_type", value.to_string())));
+    data.extend(info.url.map(|url| param("url", url)));
+    data.extend(info.summary.map(|summary| param("summary", summary)));
+    data.extend(info.cache.map(|value| param("cache", u8::from(value).to_string())));
+    data.extend(info.proxy.map(|value| param("proxy", u8::from(value).to_string())));
+    data.extend(info.timeout.map(|value| param("timeout", value.to_string())));
+    CqSegment::new("image", data)
+}
+
+fn parse_image(segment: &CqSegment) -> Option<ImageInfo> {
+    let url = segment.get("url").map(str::to_string);
+    let flag = |key: &str| segment.get(key).map(|value| value == "1" || value == "true");
+    Some(ImageInfo {
+        file: segment.get("file").map(str::to_string).or_else(|| url.clone())?,
+        url,
+        flash: segment.get("type") == Some("flash"),
+        sub_type: segment
+            .get("sub_type")
+            .or_else(|| segment.get("subType"))
+            .and_then(|value| value.parse().ok()),
+        summary: segment.get("summary").map(str::to_string),
+        cache: flag("cache"),
+        proxy: flag("proxy"),
+        timeout: segment.get("timeout").and_then(|value| value.parse().ok()),
+    })
+}
+
+fn file_to_cq(r#type: &str, info: FileInfo) -> CqSegment {
+    let param = |k: &str, v: String| (k.to_string(), v);
+    let mut data = vec![param("file", info.file)];
+    data.extend(info.url.map(|url| param("url", url)));
+    data.extend(info.name.map(|name| param("name", name)));
+    data.extend(info.size.map(|size| param("file_size", size.to_string())));
+    data.extend(info.file_id.map(|file_id| param("file_id", file_id)));
+    CqSegment::new(r#type, data)
+}
+
+fn parse_file(segment: &CqSegment) -> Option<FileInfo> {
+    let url = segment.get("url").map(str::to_string);
+    Some(FileInfo {
+        file: segment.get("file").map(str::to_string).or_else(|| url.clone())?,
+        url,
+        name: segment.get("name").map(str::to_string),
+        size: segment
+            .get("file_size")
+            .or_else(|| segment.get("size"))
+            .and_then(|size| size.parse().ok()),
+        file_id: segment.get("file_id").map(str::to_string),
+    })
+}
+
+fn parse_share(segment: &CqSegment) -> Option<OneBotSegment> {
+    Some(OneBotSegment::Share {
+        url: segment.get("url")?.to_string(),
+        title: segment.get("title")?.to_string(),
+        content: segment.get("content").map(str::to_string),
+        image: segment.get("image").map(str::to_string),
+    })
+}
+
+fn parse_location(segment: &CqSegment) -> Option<OneBotSegment> {
+    let lat: f64 = segment.get("lat")?.parse().ok()?;
+    let lon: f64 = segment.get("lon")?.parse().ok()?;
+    Some(OneBotSegment::location((lat, lon)))
+}
+
+impl From<&OneBotMessage> for CqCode {
+    fn from(value: &OneBotMessage) -> Self {
+        value.iter().map(|segment| segment.clone().into()).collect()
+    }
+}
+
+impl From<CqCode> for OneBotMessage {
+    fn from(value: CqCode) -> Self {
+        let segments = value.into_iter().map(Into::into).collect();
+        OneBotMessage::new(None, segments)
+    }
+}
+
+impl OneBotMessage {
+    /// 以 CQ 码形式表示消息，便于记录日志
+    pub fn to_cq_string(&self) -> String {
+        CqCode::from(self).to_string()
+    }
+    /// 从 CQ 码字符串构造消息
+    pub fn from_cq_string(s: &str) -> Self {
+        CqCode::parse(s).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(key: &str, value: &str) -> (String, String) {
+        (key.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        assert_eq!(escape("a&b[c]", false), "a&amp;b&#91;c&#93;");
+        assert_eq!(escape("a,b", false), "a,b");
+        assert_eq!(escape("a,b", true), "a&#44;b");
+    }
+
+    #[test]
+    fn unescape_reverses_escape() {
+        for text in ["a&b[c],d", "&#91;", "&amp;#91;", "[CQ:at,qq=1]", "&&[[]]"] {
+            assert_eq!(unescape(&escape(text, false)), text);
+            assert_eq!(unescape(&escape(text, true)), text);
+        }
+        // `&amp;#91;` 是原文 `&#91;` 转义后的结果，只还原一次
+        assert_eq!(unescape("&amp;#91;"), "&#91;");
+    }
+
+    #[test]
+    fn keeps_commas_in_param_values() {
+        let s = "[CQ:image,file=a.jpg,url=http://x/?a=1&amp;b=2&#44;3]";
+        let code = CqCode::parse(s);
+        assert_eq!(
+            code.0,
+            vec![CqSegment::new(
+                "image",
+                vec![param("file", "a.jpg"), param("url", "http://x/?a=1&b=2,3")],
+            )]
+        );
+        assert_eq!(code.to_string(), s);
+    }
+
+    #[test]
+    fn unclosed_code_is_plain_text() {
+        let code = CqCode::parse("hi[CQ:at,qq=123");
+        assert_eq!(code.0, vec![CqSegment::text("hi[CQ:at,qq=123")]);
+    }
+
+    #[test]
+    fn round_trips_mixed_text_and_codes() {
+        let s = "你好&#91;[CQ:at,qq=123] [CQ:face,id=178]&amp;再见&#93;";
+        let code = CqCode::parse(s);
+        assert_eq!(
+            code.0,
+            vec![
+                CqSegment::text("你好["),
+                CqSegment::new("at", vec![param("qq", "123")]),
+                CqSegment::text(" "),
+                CqSegment::new("face", vec![param("id", "178")]),
+                CqSegment::text("&再见]"),
+            ]
+        );
+        assert_eq!(code.to_string(), s);
+    }
+}
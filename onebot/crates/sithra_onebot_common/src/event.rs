@@ -0,0 +1,219 @@
+//! OneBot 特有的事件
+//!
+//! 消息本身仍以 sithra 的 `MessageEvent` 发出。`MessageEvent` 无法携带适配器特有的字段，
+//! 因此匿名群消息的 `MessageEvent` 只以匿名名称作为发送者昵称，匿名 ID 与禁言所需的 flag
+//! 通过单独的 [`GroupAnonymousMessageEvent`] 发出，插件需按 `message_id` 自行关联两者。
+use ioevent::Event;
+use serde::{Deserialize, Serialize};
+use sithra_common::model::{Channel, GenericId, MessageId, User, UserId};
+
+/* 消息事件 */
+
+/// 匿名发送者信息
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Anonymous {
+    /// 匿名用户 ID
+    pub id: String,
+    /// 匿名用户名称
+    pub name: String,
+    /// 匿名用户 flag，用于禁言匿名用户
+    pub flag: String,
+}
+
+/// 匿名群消息的发送者信息
+///
+/// 先于对应的 `MessageEvent` 发出，可通过 `message_id` 关联；
+/// 该 `MessageEvent` 的发送者昵称为匿名名称，消息内容不含匿名信息。
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct GroupAnonymousMessageEvent {
+    pub generic_id: GenericId,
+    /// 所在群
+    pub channel: Channel,
+    /// 消息 ID
+    pub message_id: MessageId,
+    /// 匿名发送者
+    pub anonymous: Anonymous,
+}
+
+/* 通知事件 */
+
+/// 群成员增加
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct GroupIncreaseEvent {
+    pub generic_id: GenericId,
+    /// 所在群
+    pub channel: Channel,
+    /// 加入者
+    pub user_id: UserId,
+    /// 操作者（同意加群的管理员或邀请者）
+    pub operator_id: UserId,
+    /// 加入方式（`approve` 管理员同意，`invite` 管理员邀请）
+    pub sub_type: String,
+}
+
+/// 群成员减少
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct GroupDecreaseEvent {
+    pub generic_id: GenericId,
+    /// 所在群
+    pub channel: Channel,
+    /// 离开者
+    pub user_id: UserId,
+    /// 操作者（主动退群时与 `user_id` 相同）
+    pub operator_id: UserId,
+    /// 离开方式（`leave` 主动退群，`kick` 成员被踢，`kick_me` 机器人被踢）
+    pub sub_type: String,
+}
+
+/// 群禁言
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct GroupBanEvent {
+    pub generic_id: GenericId,
+    /// 所在群
+    pub channel: Channel,
+    /// 被禁言者（全员禁言时为 0）
+    pub user_id: UserId,
+    /// 操作者
+    pub operator_id: UserId,
+    /// 禁言时长（秒）
+    pub duration: u64,
+    /// 事件类型（`ban` 禁言，`lift_ban` 解除禁言）
+    pub sub_type: String,
+}
+
+/// 群管理员变动
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct GroupAdminEvent {
+    pub generic_id: GenericId,
+    /// 所在群
+    pub channel: Channel,
+    /// 变动的成员
+    pub user_id: UserId,
+    /// 事件类型（`set` 设置，`unset` 取消）
+    pub sub_type: String,
+}
+
+/// 群消息撤回
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct GroupRecallEvent {
+    pub generic_id: GenericId,
+    /// 所在群
+    pub channel: Channel,
+    /// 消息发送者
+    pub user_id: UserId,
+    /// 撤回操作者
+    pub operator_id: UserId,
+    /// 被撤回的消息 ID
+    pub message_id: MessageId,
+}
+
+/// 好友消息撤回
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct FriendRecallEvent {
+    pub generic_id: GenericId,
+    /// 私聊频道
+    pub channel: Channel,
+    /// 好友
+    pub user_id: UserId,
+    /// 被撤回的消息 ID
+    pub message_id: MessageId,
+}
+
+/// 好友添加
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct FriendAddEvent {
+    pub generic_id: GenericId,
+    /// 新添加的好友
+    pub user_id: UserId,
+}
+
+/// 群文件上传
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct GroupUploadEvent {
+    pub generic_id: GenericId,
+    /// 所在群
+    pub channel: Channel,
+    /// 上传者
+    pub user_id: UserId,
+    /// 文件信息
+    pub file: GroupFile,
+}
+
+/// 群文件信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupFile {
+    /// 文件 ID
+    pub id: String,
+    /// 文件名
+    pub name: String,
+    /// 文件大小（字节）
+    pub size: u64,
+    /// 文件所在的服务器 ID
+    pub busid: u64,
+}
+
+/// 戳一戳
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct PokeEvent {
+    pub generic_id: GenericId,
+    /// 所在频道（好友戳一戳时为私聊频道）
+    pub channel: Channel,
+    /// 发起者
+    pub user: User,
+    /// 被戳者
+    pub target_id: UserId,
+}
+
+/// 群红包运气王
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct LuckyKingEvent {
+    pub generic_id: GenericId,
+    /// 所在群
+    pub channel: Channel,
+    /// 红包发送者
+    pub user: User,
+    /// 运气王
+    pub target_id: UserId,
+}
+
+/// 群成员荣誉变更
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct HonorEvent {
+    pub generic_id: GenericId,
+    /// 所在群
+    pub channel: Channel,
+    /// 获得荣誉的成员
+    pub user: User,
+    /// 荣誉类型（`talkative` 龙王，`performer` 群聊之火，`emotion` 快乐源泉）
+    pub honor_type: String,
+}
+
+/* 请求事件 */
+
+/// 加好友请求
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct FriendRequestEvent {
+    pub generic_id: GenericId,
+    /// 请求者
+    pub user_id: UserId,
+    /// 验证信息
+    pub comment: String,
+    /// 请求 flag，处理请求时传入
+    pub flag: String,
+}
+
+/// 加群请求或邀请
+#[derive(Debug, Clone, Serialize, Deserialize, Event)]
+pub struct GroupRequestEvent {
+    pub generic_id: GenericId,
+    /// 目标群
+    pub channel: Channel,
+    /// 请求者
+    pub user_id: UserId,
+    /// 验证信息
+    pub comment: String,
+    /// 请求 flag，处理请求时传入
+    pub flag: String,
+    /// 请求类型（`add` 加群请求，`invite` 邀请机器人入群），处理请求时传入
+    pub sub_type: String,
+}
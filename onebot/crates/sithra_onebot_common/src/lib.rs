@@ -1 +1,4 @@
-pub mod message;
\ No newline at end of file
+pub mod api;
+pub mod cq;
+pub mod event;
+pub mod message;
@@ -1,4 +1,7 @@
+use base64::{Engine, prelude::BASE64_STANDARD};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
 use sithra_common::kv;
 use sithra_common::message::*;
 use sithra_common::model::*;
@@ -8,12 +11,20 @@ use sithra_common::vec;
 pub enum OneBotSegment {
     /// 文本(文本内容)
     Text(String),
-    /// 图片(图片 URL)
-    Image(String),
+    /// 图片
+    Image(ImageInfo),
+    /// QQ 表情(表情 ID)
+    Face(String),
     /// 提及用户(用户 ID)
     At(UserId),
+    /// 提及全体成员
+    AtAll,
     /// 语音(语音 URL)
     Record(String),
+    /// 视频
+    Video(FileInfo),
+    /// 文件
+    File(FileInfo),
     /// 群聊戳一戳
     Poke(UserId),
     /// 位置
@@ -22,8 +33,299 @@ pub enum OneBotSegment {
     Reply(MessageId),
     /// 合并转发
     Forward(ForwardId),
-    /// 未知消息段
-    Unknown,
+    /// 链接分享
+    Share {
+        url: String,
+        title: String,
+        content: Option<String>,
+        image: Option<String>,
+    },
+    /// 推荐好友或群
+    Contact { kind: ContactKind, id: String },
+    /// JSON 卡片(JSON 内容)
+    Json(String),
+    /// XML 卡片(XML 内容)
+    Xml(String),
+    /// 音乐分享
+    Music(Music),
+    /// 掷骰子(接收时为点数)
+    Dice(Option<u8>),
+    /// 猜拳(接收时为结果)
+    Rps(Option<u8>),
+    /// 窗口抖动(戳一戳)
+    Shake,
+    /// 未知消息段，保留原始的类型与参数以便原样发送
+    Unknown {
+        r#type: String,
+        data: Map<String, Value>,
+    },
+}
+/// 由内存中的数据生成 `base64://` 形式的文件来源
+pub fn base64_source(bytes: impl AsRef<[u8]>) -> String {
+    format!("base64://{}", BASE64_STANDARD.encode(bytes))
+}
+
+/// 由本地路径生成 `file://` 形式的文件来源
+///
+/// 相对路径会基于当前工作目录转为绝对路径。
+pub fn file_source(path: impl AsRef<Path>) -> String {
+    let path = path.as_ref();
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let path = path.to_string_lossy().replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        // Windows 路径，如 `C:/...`
+        format!("file:///{}", path)
+    }
+}
+
+/// 图片消息段的信息
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ImageInfo {
+    /// 发送时为文件路径、URL 或 base64；接收时为 OneBot 实现给出的文件名
+    pub file: String,
+    /// 下载地址
+    pub url: Option<String>,
+    /// 是否为闪照
+    pub flash: bool,
+    /// 图片子类型，`1` 为表情包
+    pub sub_type: Option<u32>,
+    /// 图片外显文字
+    pub summary: Option<String>,
+    /// 发送网络图片时是否使用已缓存的文件
+    pub cache: Option<bool>,
+    /// 发送网络图片时是否通过代理下载
+    pub proxy: Option<bool>,
+    /// 发送网络图片时的下载超时(秒)
+    pub timeout: Option<u64>,
+}
+impl ImageInfo {
+    /// 表情包的图片子类型
+    pub const STICKER: u32 = 1;
+
+    pub fn new<S: ToString>(file: S) -> Self {
+        Self {
+            file: file.to_string(),
+            ..Default::default()
+        }
+    }
+    /// 由内存中的图片数据（如渲染出的 PNG）生成
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Self {
+        Self::new(base64_source(bytes))
+    }
+    /// 由本地图片文件生成
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        Self::new(file_source(path))
+    }
+    /// 设为闪照
+    pub fn flash(mut self) -> Self {
+        self.flash = true;
+        self
+    }
+    /// 设为表情包
+    pub fn sticker(mut self) -> Self {
+        self.sub_type = Some(Self::STICKER);
+        self
+    }
+    /// 设置外显文字
+    pub fn with_summary<S: ToString>(mut self, summary: S) -> Self {
+        self.summary = Some(summary.to_string());
+        self
+    }
+    /// 是否为表情包
+    pub fn is_sticker(&self) -> bool {
+        self.sub_type == Some(Self::STICKER)
+    }
+    /// 图片的访问地址，接收到的图片优先使用 `url`
+    pub fn url(&self) -> &str {
+        self.url.as_deref().unwrap_or(&self.file)
+    }
+    fn from_raw(segment: &mut SegmentRaw) -> Option<Self> {
+        let url = segment.kv.remove("url");
+        let flag = |value: String| value == "1" || value == "true";
+        Some(Self {
+            file: segment.kv.remove("file").or_else(|| url.clone())?,
+            url,
+            flash: segment.kv.remove("type").is_some_and(|kind| kind == "flash"),
+            sub_type: segment.kv.remove("sub_type").and_then(|value| value.parse().ok()),
+            summary: segment.kv.remove("summary"),
+            cache: segment.kv.remove("cache").map(flag),
+            proxy: segment.kv.remove("proxy").map(flag),
+            timeout: segment.kv.remove("timeout").and_then(|value| value.parse().ok()),
+        })
+    }
+    fn into_raw(self) -> SegmentRaw {
+        // `url` 与其他适配器的图片消息段一致，base64 数据不重复给出
+        let url = match self.url {
+            Some(url) => Some(url),
+            None if self.file.starts_with("base64://") => None,
+            None => Some(self.file.clone()),
+        };
+        let mut raw = SegmentRaw::new("image", kv! { "file": &self.file });
+        if let Some(url) = url {
+            raw.kv.insert("url".to_string(), url);
+        }
+        if self.flash {
+            raw.kv.insert("type".to_string(), "flash".to_string());
+        }
+        let optional = [
+            ("sub_type", self.sub_type.map(|value| value.to_string())),
+            ("summary", self.summary),
+            ("cache", self.cache.map(|value| u8::from(value).to_string())),
+            ("proxy", self.proxy.map(|value| u8::from(value).to_string())),
+            ("timeout", self.timeout.map(|value| value.to_string())),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                raw.kv.insert(key.to_string(), value);
+            }
+        }
+        raw
+    }
+}
+/// 视频、文件消息段的文件信息
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct FileInfo {
+    /// 发送时为文件路径、URL 或 base64；接收时为 OneBot 实现给出的文件标识
+    pub file: String,
+    /// 下载地址
+    pub url: Option<String>,
+    /// 文件名
+    pub name: Option<String>,
+    /// 文件大小(字节)
+    pub size: Option<u64>,
+    /// 文件 ID
+    pub file_id: Option<String>,
+}
+impl FileInfo {
+    pub fn new<S: ToString>(file: S) -> Self {
+        Self {
+            file: file.to_string(),
+            ..Default::default()
+        }
+    }
+    /// 设置文件名
+    pub fn with_name<S: ToString>(mut self, name: S) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+    fn from_raw(segment: &mut SegmentRaw) -> Option<Self> {
+        let url = segment.kv.remove("url");
+        Some(Self {
+            file: segment.kv.remove("file").or_else(|| url.clone())?,
+            url,
+            name: segment.kv.remove("name"),
+            size: segment.kv.remove("size").and_then(|size| size.parse().ok()),
+            file_id: segment.kv.remove("file_id"),
+        })
+    }
+    fn into_raw(self, r#type: &str) -> SegmentRaw {
+        let mut raw = SegmentRaw::new(r#type, kv! { "file": &self.file });
+        if let Some(url) = self.url {
+            raw.kv.insert("url".to_string(), url);
+        }
+        if let Some(name) = self.name {
+            raw.kv.insert("name".to_string(), name);
+        }
+        if let Some(size) = self.size {
+            raw.kv.insert("size".to_string(), size.to_string());
+        }
+        if let Some(file_id) = self.file_id {
+            raw.kv.insert("file_id".to_string(), file_id);
+        }
+        raw
+    }
+}
+/// 音乐分享
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub enum Music {
+    /// 音乐平台上的歌曲
+    Platform {
+        /// 平台，`qq`、`163` 或 `xm`
+        platform: String,
+        /// 歌曲 ID
+        id: String,
+    },
+    /// 自定义音乐
+    Custom {
+        /// 点击后跳转的 URL
+        url: String,
+        /// 音乐 URL
+        audio: String,
+        /// 标题
+        title: String,
+        /// 内容描述
+        content: Option<String>,
+        /// 封面图片 URL
+        image: Option<String>,
+    },
+}
+impl Music {
+    /// 从 OneBot 的参数构造，`type` 为 `custom` 时为自定义音乐
+    pub fn from_params(get: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        match get("type")?.as_str() {
+            "custom" => Some(Music::Custom {
+                url: get("url")?,
+                audio: get("audio")?,
+                title: get("title")?,
+                content: get("content"),
+                image: get("image"),
+            }),
+            platform => Some(Music::Platform {
+                platform: platform.to_string(),
+                id: get("id")?,
+            }),
+        }
+    }
+    /// 转为 OneBot 的参数
+    pub fn into_params(self) -> Vec<(String, String)> {
+        let param = |k: &str, v: String| (k.to_string(), v);
+        match self {
+            Music::Platform { platform, id } => vec![param("type", platform), param("id", id)],
+            Music::Custom {
+                url,
+                audio,
+                title,
+                content,
+                image,
+            } => {
+                let mut params = vec![
+                    param("type", "custom".to_string()),
+                    param("url", url),
+                    param("audio", audio),
+                    param("title", title),
+                ];
+                params.extend(content.map(|content| param("content", content)));
+                params.extend(image.map(|image| param("image", image)));
+                params
+            }
+        }
+    }
+}
+/// 推荐的联系人类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ContactKind {
+    /// 好友
+    User,
+    /// 群
+    Group,
+}
+impl ContactKind {
+    /// OneBot 中的 `type` 参数
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContactKind::User => "qq",
+            ContactKind::Group => "group",
+        }
+    }
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "qq" => Some(ContactKind::User),
+            "group" => Some(ContactKind::Group),
+            _ => None,
+        }
+    }
 }
 /// 合并转发 ID
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -50,16 +352,56 @@ impl OneBotSegment {
     }
     /// 生成图片消息段
     pub fn img<S: ToString>(url: S) -> Self {
-        Self::Image(url.to_string())
+        Self::Image(ImageInfo::new(url))
+    }
+    /// 由内存中的图片数据生成图片消息段
+    pub fn img_bytes(bytes: impl AsRef<[u8]>) -> Self {
+        Self::Image(ImageInfo::from_bytes(bytes))
+    }
+    /// 由本地图片文件生成图片消息段
+    pub fn img_path(path: impl AsRef<Path>) -> Self {
+        Self::Image(ImageInfo::from_path(path))
+    }
+    /// 生成闪照消息段
+    pub fn flash_img<S: ToString>(url: S) -> Self {
+        Self::Image(ImageInfo::new(url).flash())
+    }
+    /// 生成表情包消息段
+    pub fn sticker<S: ToString, T: ToString>(url: S, summary: T) -> Self {
+        Self::Image(ImageInfo::new(url).sticker().with_summary(summary))
+    }
+    /// 生成 QQ 表情消息段
+    pub fn face<S: ToString>(id: S) -> Self {
+        Self::Face(id.to_string())
     }
     /// 生成提及用户消息段
     pub fn at<S: Into<UserId>>(user_id: S) -> Self {
         Self::At(user_id.into())
     }
+    /// 生成提及全体成员消息段
+    pub fn at_all() -> Self {
+        Self::AtAll
+    }
     /// 生成语音消息段
     pub fn record<S: ToString>(url: S) -> Self {
         Self::Record(url.to_string())
     }
+    /// 由内存中的音频数据生成语音消息段
+    pub fn record_bytes(bytes: impl AsRef<[u8]>) -> Self {
+        Self::Record(base64_source(bytes))
+    }
+    /// 由本地音频文件生成语音消息段
+    pub fn record_path(path: impl AsRef<Path>) -> Self {
+        Self::Record(file_source(path))
+    }
+    /// 生成视频消息段
+    pub fn video<S: ToString>(file: S) -> Self {
+        Self::Video(FileInfo::new(file))
+    }
+    /// 生成文件消息段
+    pub fn file<S: ToString, N: ToString>(file: S, name: N) -> Self {
+        Self::File(FileInfo::new(file).with_name(name))
+    }
     /// 生成群聊戳一戳消息段
     pub fn poke<S: Into<UserId>>(user_id: S) -> Self {
         Self::Poke(user_id.into())
@@ -79,16 +421,118 @@ impl OneBotSegment {
     pub fn forward<S: Into<ForwardId>>(forward_id: S) -> Self {
         Self::Forward(forward_id.into())
     }
+    /// 生成链接分享消息段
+    pub fn share<U: ToString, T: ToString>(url: U, title: T) -> Self {
+        Self::Share {
+            url: url.to_string(),
+            title: title.to_string(),
+            content: None,
+            image: None,
+        }
+    }
+    /// 生成推荐好友消息段
+    pub fn contact_user<S: ToString>(user_id: S) -> Self {
+        Self::Contact {
+            kind: ContactKind::User,
+            id: user_id.to_string(),
+        }
+    }
+    /// 生成推荐群消息段
+    pub fn contact_group<S: ToString>(group_id: S) -> Self {
+        Self::Contact {
+            kind: ContactKind::Group,
+            id: group_id.to_string(),
+        }
+    }
+    /// 生成 JSON 卡片消息段
+    pub fn json<S: ToString>(data: S) -> Self {
+        Self::Json(data.to_string())
+    }
+    /// 生成 XML 卡片消息段
+    pub fn xml<S: ToString>(data: S) -> Self {
+        Self::Xml(data.to_string())
+    }
+    /// 生成音乐平台的音乐分享消息段
+    pub fn music<P: ToString, S: ToString>(platform: P, id: S) -> Self {
+        Self::Music(Music::Platform {
+            platform: platform.to_string(),
+            id: id.to_string(),
+        })
+    }
+    /// 生成自定义音乐分享消息段
+    pub fn custom_music<U: ToString, A: ToString, T: ToString>(url: U, audio: A, title: T) -> Self {
+        Self::Music(Music::Custom {
+            url: url.to_string(),
+            audio: audio.to_string(),
+            title: title.to_string(),
+            content: None,
+            image: None,
+        })
+    }
+    /// 生成掷骰子消息段
+    pub fn dice() -> Self {
+        Self::Dice(None)
+    }
+    /// 生成猜拳消息段
+    pub fn rps() -> Self {
+        Self::Rps(None)
+    }
+    /// 生成窗口抖动消息段
+    pub fn shake() -> Self {
+        Self::Shake
+    }
+    /// 生成未知消息段，用于发送本适配器未建模的、实现特有的消息段
+    pub fn unknown<S: ToString>(r#type: S, data: Map<String, Value>) -> Self {
+        Self::Unknown {
+            r#type: r#type.to_string(),
+            data,
+        }
+    }
+}
+
+/// 将消息段参数转为字符串
+///
+/// OneBot 11 中消息段参数一般为字符串，嵌套结构（如按钮键盘）编码为 JSON。
+pub fn param_to_string(value: Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(value) => Some(value),
+        value => Some(value.to_string()),
+    }
 }
+
 impl FromRawSegment for OneBotSegment {
     fn from_raw_segment(segment: &mut SegmentRaw) -> Option<Self> {
+        Self::from_known_segment(segment).or_else(|| {
+            // 由本适配器序列化的未知消息段，参数整体以 JSON 编码存放在 `data` 中；
+            // 其他来源的消息段则按字符串参数处理
+            let encoded = match segment.kv.get(UNKNOWN_DATA_KEY) {
+                Some(data) if segment.kv.len() == 1 => serde_json::from_str(data).ok(),
+                _ => None,
+            };
+            let data = encoded.unwrap_or_else(|| {
+                std::mem::take(&mut segment.kv)
+                    .into_iter()
+                    .map(|(key, value)| (key, Value::String(value)))
+                    .collect()
+            });
+            Some(OneBotSegment::unknown(&segment.r#type, data))
+        })
+    }
+}
+impl OneBotSegment {
+    fn from_known_segment(segment: &mut SegmentRaw) -> Option<Self> {
         match segment.r#type.as_str() {
             "text" => Some(OneBotSegment::Text(segment.kv.remove("content")?)),
-            "image" => Some(OneBotSegment::Image(segment.kv.remove("url")?)),
-            "at" => Some(OneBotSegment::At(UserId::new(
-                segment.kv.remove("user_id")?,
-            ))),
+            "image" => Some(OneBotSegment::Image(ImageInfo::from_raw(segment)?)),
+            "face" => Some(OneBotSegment::Face(segment.kv.remove("id")?)),
+            "at" => match segment.kv.remove("user_id")? {
+                user_id if user_id == "all" => Some(OneBotSegment::AtAll),
+                user_id => Some(OneBotSegment::At(UserId::new(user_id))),
+            },
             "record" => Some(OneBotSegment::Record(segment.kv.remove("url")?)),
+            "video" => Some(OneBotSegment::Video(FileInfo::from_raw(segment)?)),
+            "file" => Some(OneBotSegment::File(FileInfo::from_raw(segment)?)),
             "poke" => Some(OneBotSegment::Poke(UserId::new(
                 segment.kv.remove("user_id")?,
             ))),
@@ -100,7 +544,29 @@ impl FromRawSegment for OneBotSegment {
                 segment.kv.remove("message_id")?,
             ))),
             "forward" => Some(OneBotSegment::Forward(ForwardId(segment.kv.remove("id")?))),
-            _ => Some(OneBotSegment::Unknown),
+            "share" => Some(OneBotSegment::Share {
+                url: segment.kv.remove("url")?,
+                title: segment.kv.remove("title")?,
+                content: segment.kv.remove("content"),
+                image: segment.kv.remove("image"),
+            }),
+            "contact" => Some(OneBotSegment::Contact {
+                kind: ContactKind::parse(segment.kv.get("type")?)?,
+                id: segment.kv.remove("id")?,
+            }),
+            "json" => Some(OneBotSegment::Json(segment.kv.remove("data")?)),
+            "xml" => Some(OneBotSegment::Xml(segment.kv.remove("data")?)),
+            "music" => Some(OneBotSegment::Music(Music::from_params(|key| {
+                segment.kv.get(key).cloned()
+            })?)),
+            "dice" => Some(OneBotSegment::Dice(
+                segment.kv.remove("result").and_then(|result| result.parse().ok()),
+            )),
+            "rps" => Some(OneBotSegment::Rps(
+                segment.kv.remove("result").and_then(|result| result.parse().ok()),
+            )),
+            "shake" => Some(OneBotSegment::Shake),
+            _ => None,
         }
     }
 }
@@ -130,9 +596,13 @@ impl MessageSerializer for OneBotMessageSerializer {
     fn serialize(message: Self::Input) -> Option<SegmentRaw> {
         match message {
             OneBotSegment::Text(text) => Some(SegmentRaw::text(text)),
-            OneBotSegment::Image(url) => Some(SegmentRaw::img(url)),
+            OneBotSegment::Image(info) => Some(info.into_raw()),
+            OneBotSegment::Face(id) => Some(SegmentRaw::new("face", kv! { "id": &id })),
             OneBotSegment::At(user_id) => Some(SegmentRaw::at(user_id.to_string())),
+            OneBotSegment::AtAll => Some(SegmentRaw::at("all")),
             OneBotSegment::Record(url) => Some(SegmentRaw::new("record", kv! { "url": &url })),
+            OneBotSegment::Video(info) => Some(info.into_raw("video")),
+            OneBotSegment::File(info) => Some(info.into_raw("file")),
             OneBotSegment::Poke(user_id) => Some(SegmentRaw::new(
                 "poke",
                 kv! { "user_id": &user_id.to_string() },
@@ -148,10 +618,52 @@ impl MessageSerializer for OneBotMessageSerializer {
             OneBotSegment::Forward(forward_id) => {
                 Some(SegmentRaw::new("forward", kv! { "id": &forward_id.0 }))
             }
-            OneBotSegment::Unknown => None,
+            OneBotSegment::Share {
+                url,
+                title,
+                content,
+                image,
+            } => {
+                let mut raw = SegmentRaw::new("share", kv! { "url": &url, "title": &title });
+                if let Some(content) = content {
+                    raw.kv.insert("content".to_string(), content);
+                }
+                if let Some(image) = image {
+                    raw.kv.insert("image".to_string(), image);
+                }
+                Some(raw)
+            }
+            OneBotSegment::Contact { kind, id } => Some(SegmentRaw::new(
+                "contact",
+                kv! { "type": kind.as_str(), "id": &id },
+            )),
+            OneBotSegment::Json(data) => Some(SegmentRaw::new("json", kv! { "data": &data })),
+            OneBotSegment::Xml(data) => Some(SegmentRaw::new("xml", kv! { "data": &data })),
+            OneBotSegment::Music(music) => Some(SegmentRaw::new(
+                "music",
+                music.into_params().into_iter().collect(),
+            )),
+            OneBotSegment::Dice(result) => Some(result_segment("dice", result)),
+            OneBotSegment::Rps(result) => Some(result_segment("rps", result)),
+            OneBotSegment::Shake => Some(SegmentRaw::new("shake", Default::default())),
+            OneBotSegment::Unknown { r#type, data } => {
+                // 参数可能是数字、布尔或嵌套结构，整体编码以免在总线上丢失类型
+                let data = serde_json::to_string(&data).ok()?;
+                Some(SegmentRaw::new(r#type, kv! { "data": &data }))
+            }
         }
     }
 }
+/// 未知消息段在总线上存放 JSON 编码参数的键
+const UNKNOWN_DATA_KEY: &str = "data";
+/// 掷骰子、猜拳消息段，发送时没有结果
+fn result_segment(r#type: &str, result: Option<u8>) -> SegmentRaw {
+    let mut raw = SegmentRaw::new(r#type, Default::default());
+    if let Some(result) = result {
+        raw.kv.insert("result".to_string(), result.to_string());
+    }
+    raw
+}
 impl MessageDeserializer for OneBotMessageSerializer {
     type Output = OneBotSegment;
     fn deserialize(mut segment: SegmentRaw) -> Option<Self::Output> {
@@ -188,4 +700,44 @@ impl Message for OneBotMessage {
     fn iter(&self) -> impl Iterator<Item = &Self::Segment> {
         self.inner.iter()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn round_trip(segment: OneBotSegment) -> OneBotSegment {
+        let mut raw = OneBotMessageSerializer::serialize(segment).unwrap();
+        OneBotSegment::from_raw_segment(&mut raw).unwrap()
+    }
+
+    #[test]
+    fn unknown_segment_keeps_param_types() {
+        let data = json!({
+            "emoji_package_id": 123,
+            "emoji_id": "abc",
+            "is_animated": true,
+            "ratio": 1.5,
+            "content": "{\"looks\":\"like json\"}",
+            "list": "[1, 2]",
+            "keyboard": { "rows": [{ "buttons": [] }] },
+        });
+        let Value::Object(data) = data else { unreachable!() };
+        let segment = round_trip(OneBotSegment::unknown("mface", data.clone()));
+        let OneBotSegment::Unknown { r#type, data: back } = segment else {
+            panic!("未知消息段应保持为 Unknown");
+        };
+        assert_eq!(r#type, "mface");
+        assert_eq!(back, data);
+    }
+
+    #[test]
+    fn unknown_segment_from_plain_params() {
+        let mut raw = SegmentRaw::new("markdown", kv! { "content": "{\"a\":1}" });
+        let segment = OneBotSegment::from_raw_segment(&mut raw).unwrap();
+        let OneBotSegment::Unknown { data, .. } = segment else {
+            panic!("未知消息段应保持为 Unknown");
+        };
+        assert_eq!(data.get("content"), Some(&json!("{\"a\":1}")));
+    }
+}